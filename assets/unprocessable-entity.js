@@ -0,0 +1,8 @@
+// htmx doesn't swap responses with an error status by default.
+// Forms that fail validation are re-rendered with the status 422 though, so they must be shown.
+document.addEventListener('htmx:beforeSwap', function (event) {
+    if (event.detail.xhr.status === 422) {
+        event.detail.shouldSwap = true;
+        event.detail.isError = false;
+    }
+});
@@ -0,0 +1,30 @@
+CREATE TABLE nobts
+(
+    id       TEXT PRIMARY KEY NOT NULL,
+    title    TEXT             NOT NULL,
+    currency TEXT             NOT NULL
+);
+
+CREATE TABLE participants
+(
+    nobt_id TEXT NOT NULL REFERENCES nobts (id),
+    name    TEXT NOT NULL,
+    PRIMARY KEY (nobt_id, name)
+);
+
+CREATE TABLE bills
+(
+    id      INTEGER PRIMARY KEY AUTOINCREMENT,
+    nobt_id TEXT    NOT NULL REFERENCES nobts (id),
+    name    TEXT    NOT NULL,
+    total   REAL    NOT NULL,
+    debtee  TEXT    NOT NULL,
+    deleted INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE bill_debtors
+(
+    bill_id INTEGER NOT NULL REFERENCES bills (id),
+    name    TEXT    NOT NULL,
+    PRIMARY KEY (bill_id, name)
+);
@@ -0,0 +1,13 @@
+ALTER TABLE bills
+    ADD COLUMN created_at TEXT NOT NULL DEFAULT '1970-01-01 00:00:00';
+
+CREATE TABLE payments
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    nobt_id    TEXT NOT NULL REFERENCES nobts (id),
+    sender     TEXT NOT NULL,
+    recipient  TEXT NOT NULL,
+    amount     REAL NOT NULL,
+    note       TEXT,
+    created_at TEXT NOT NULL
+);
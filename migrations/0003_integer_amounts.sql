@@ -0,0 +1,16 @@
+-- Amounts are stored as integer minor units of the nobt's currency.
+-- All currencies supported so far have two decimal places.
+
+ALTER TABLE bills
+    ADD COLUMN total_minor_units INTEGER NOT NULL DEFAULT 0;
+UPDATE bills
+SET total_minor_units = CAST(ROUND(total * 100) AS INTEGER);
+ALTER TABLE bills
+    DROP COLUMN total;
+
+ALTER TABLE payments
+    ADD COLUMN amount_minor_units INTEGER NOT NULL DEFAULT 0;
+UPDATE payments
+SET amount_minor_units = CAST(ROUND(amount * 100) AS INTEGER);
+ALTER TABLE payments
+    DROP COLUMN amount;
@@ -0,0 +1,9 @@
+-- Bills can be split unequally. Depending on the split mode of a bill, the weight of a debtor is
+-- their number of shares, their percentage in hundredths of a percent or their exact amount in minor units.
+-- Bills that are split equally have no weights.
+
+ALTER TABLE bills
+    ADD COLUMN split_mode TEXT NOT NULL DEFAULT 'equal';
+
+ALTER TABLE bill_debtors
+    ADD COLUMN weight INTEGER;
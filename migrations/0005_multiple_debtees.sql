@@ -0,0 +1,16 @@
+-- A bill can be paid by several debtees, each paying part of the total.
+
+CREATE TABLE bill_debtees
+(
+    bill_id            INTEGER NOT NULL REFERENCES bills (id),
+    name               TEXT    NOT NULL,
+    amount_minor_units INTEGER NOT NULL,
+    PRIMARY KEY (bill_id, name)
+);
+
+INSERT INTO bill_debtees (bill_id, name, amount_minor_units)
+SELECT id, debtee, total_minor_units
+FROM bills;
+
+ALTER TABLE bills
+    DROP COLUMN debtee;
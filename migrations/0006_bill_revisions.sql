@@ -0,0 +1,4 @@
+-- Editing a bill stores the changes as a new bill that replaces the previous revision.
+
+ALTER TABLE bills
+    ADD COLUMN replaced_by INTEGER REFERENCES bills (id);
@@ -0,0 +1,18 @@
+-- Bills can be paid in another currency than the one of their nobt. Their amounts are stored in that currency then,
+-- along with the rate that converts them into the currency of the nobt.
+
+ALTER TABLE bills
+    ADD COLUMN currency TEXT;
+
+ALTER TABLE bills
+    ADD COLUMN exchange_rate_millionths INTEGER;
+
+-- Exchange rates maintained by whoever runs this instance, used to pre-fill the rate of bills in other currencies.
+-- Rates are in millionths, e.g. 1040000 for 1 CHF = 1.04 EUR.
+CREATE TABLE exchange_rates
+(
+    from_currency TEXT    NOT NULL,
+    to_currency   TEXT    NOT NULL,
+    millionths    INTEGER NOT NULL,
+    PRIMARY KEY (from_currency, to_currency)
+);
@@ -0,0 +1,8 @@
+-- Bills carry the day they were paid on, which can differ from the day they were added to the nobt.
+-- Existing bills were most likely paid on the day they were added.
+
+ALTER TABLE bills
+    ADD COLUMN date TEXT NOT NULL DEFAULT '';
+
+UPDATE bills
+SET date = substr(created_at, 1, 10);
@@ -0,0 +1,5 @@
+-- Bills are sorted into categories like food or transport. Built-in categories are stored by their key, e.g. 'food',
+-- anything else is the name of a category the participants made up.
+
+ALTER TABLE bills
+    ADD COLUMN category TEXT NOT NULL DEFAULT 'other';
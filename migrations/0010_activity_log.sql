@@ -0,0 +1,18 @@
+-- An append-only log of who changed what in a nobt. Entries keep the names and amounts as they were at the time, so
+-- they stay readable after bills are edited or participants are renamed.
+-- `kind` says what happened, e.g. 'bill_added'. Depending on it, `subject` is the name of the bill or participant,
+-- `detail` the other participant involved and the amount is the total of the bill or payment.
+
+CREATE TABLE activities
+(
+    id                 INTEGER PRIMARY KEY AUTOINCREMENT,
+    nobt_id            TEXT NOT NULL REFERENCES nobts (id),
+    actor              TEXT,
+    kind               TEXT NOT NULL,
+    bill_id            INTEGER REFERENCES bills (id),
+    subject            TEXT NOT NULL,
+    detail             TEXT,
+    amount_minor_units INTEGER,
+    currency           TEXT,
+    created_at         TEXT NOT NULL
+);
@@ -0,0 +1,183 @@
+use crate::money::Money;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::headers::{Cookie, HeaderMapExt};
+use axum::http::request::Parts;
+use std::convert::Infallible;
+use time::OffsetDateTime;
+
+/// An entry of the activity log of a nobt, recording who changed what.
+///
+/// The log is append-only: entries are never changed or removed, even if the bills and participants they mention are.
+#[derive(Clone, Debug)]
+pub struct Activity {
+    /// The participant who made the change, if they told us who they are.
+    pub actor: Option<String>,
+    pub event: Event,
+    pub created_at: OffsetDateTime,
+}
+
+/// A change to a nobt, with the names and amounts as they were at the time.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    BillAdded {
+        bill_id: u64,
+        name: String,
+        total: Money,
+    },
+    /// A bill was edited, `bill_id` is the ID of the new revision.
+    BillEdited {
+        bill_id: u64,
+        name: String,
+        total: Money,
+    },
+    BillDeleted {
+        bill_id: u64,
+        name: String,
+        total: Money,
+    },
+    BillRestored {
+        bill_id: u64,
+        name: String,
+        total: Money,
+    },
+    PaymentAdded {
+        from: String,
+        to: String,
+        amount: Money,
+    },
+    ParticipantAdded {
+        name: String,
+    },
+    ParticipantRenamed {
+        name: String,
+        new_name: String,
+    },
+    ParticipantRemoved {
+        name: String,
+    },
+    ParticipantsMerged {
+        name: String,
+        into: String,
+    },
+}
+
+impl Event {
+    /// The Material Symbol shown next to this event.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Event::BillAdded { .. } => "note_add",
+            Event::BillEdited { .. } => "edit",
+            Event::BillDeleted { .. } => "delete",
+            Event::BillRestored { .. } => "restore_from_trash",
+            Event::PaymentAdded { .. } => "payments",
+            Event::ParticipantAdded { .. } => "person_add",
+            Event::ParticipantRenamed { .. } => "badge",
+            Event::ParticipantRemoved { .. } => "person_remove",
+            Event::ParticipantsMerged { .. } => "merge",
+        }
+    }
+}
+
+/// The cookie that stores which participant of a nobt is using the browser.
+///
+/// It is scoped to the path of the nobt, so someone can be a different participant in every nobt.
+pub const ACTOR_COOKIE: &str = "me";
+
+/// The participant making a request, as picked on the activity page of the nobt.
+pub struct Actor(pub Option<String>);
+
+/// Marks a request whose cookie names someone who is no longer a participant of the nobt, e.g. after a rename.
+///
+/// The [`Actor`] of such a request is unknown, so that changes aren't recorded under a name nobody has anymore.
+#[derive(Clone, Copy)]
+pub struct FormerActor;
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Actor
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _: &S) -> Result<Self, Self::Rejection> {
+        if parts.extensions.get::<FormerActor>().is_some() {
+            return Ok(Actor(None));
+        }
+
+        let name = parts
+            .headers
+            .typed_get::<Cookie>()
+            .and_then(|cookie| cookie.get(ACTOR_COOKIE).and_then(decode_cookie_value));
+
+        Ok(Actor(name))
+    }
+}
+
+/// A `Set-Cookie` value that removes the actor cookie of a nobt from the browser.
+pub fn forget_actor_cookie(nobt_id: &str) -> String {
+    format!("{ACTOR_COOKIE}=; Path=/{nobt_id}; Max-Age=0; SameSite=Lax")
+}
+
+/// Encodes a name as a cookie value, which must not contain spaces, commas, semicolons or non-ASCII characters.
+pub fn encode_cookie_value(name: &str) -> String {
+    name.bytes().map(|b| format!("{b:02x}")).collect()
+}
+
+fn decode_cookie_value(value: &str) -> Option<String> {
+    if value.is_empty() || !value.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let bytes = (0..value.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(value.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_survive_the_cookie() {
+        for name in ["Thomas", "Anna Lena", "Jürgen; Sr.", "李"] {
+            assert_eq!(
+                decode_cookie_value(&encode_cookie_value(name)).as_deref(),
+                Some(name)
+            );
+        }
+
+        assert_eq!(decode_cookie_value(""), None);
+        assert_eq!(decode_cookie_value("abc"), None);
+        assert_eq!(decode_cookie_value("zz"), None);
+    }
+
+    #[tokio::test]
+    async fn former_actors_are_unknown() {
+        let request = |former: bool| {
+            let mut request = axum::http::Request::builder()
+                .header(
+                    "cookie",
+                    format!("{ACTOR_COOKIE}={}", encode_cookie_value("Tom")),
+                )
+                .body(())
+                .unwrap();
+            if former {
+                request.extensions_mut().insert(FormerActor);
+            }
+            request.into_parts().0
+        };
+
+        let Actor(actor) = Actor::from_request_parts(&mut request(false), &())
+            .await
+            .unwrap();
+        assert_eq!(actor.as_deref(), Some("Tom"));
+        let Actor(actor) = Actor::from_request_parts(&mut request(true), &())
+            .await
+            .unwrap();
+        assert_eq!(actor, None);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::money::Money;
+use crate::nobt::Nobt;
+
+/// The net balance of a participant.
+///
+/// A positive amount means the participant gets money back, a negative amount means they owe money.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Balance {
+    pub name: String,
+    pub amount: Money,
+}
+
+/// Computes the balances of all participants of a nobt from its bills and payments.
+///
+/// Deleted bills are ignored. People who are no longer participants of the nobt are only listed if they still owe or get money.
+/// All calculations happen in minor units which guarantees that the balances always sum up to exactly zero.
+pub fn balances(nobt: &Nobt) -> Vec<Balance> {
+    let zero = Money::zero(nobt.currency);
+    let mut balances = nobt
+        .participant_names()
+        .map(|name| Balance {
+            name: name.to_owned(),
+            amount: zero,
+        })
+        .collect::<Vec<_>>();
+
+    for bill in nobt.active_bills() {
+        for (debtee, amount) in bill.paid_amounts() {
+            *balance_of(&mut balances, debtee, zero) += amount;
+        }
+
+        for (debtor, share) in bill.shares() {
+            *balance_of(&mut balances, debtor, zero) -= share;
+        }
+    }
+
+    for payment in &nobt.payments {
+        *balance_of(&mut balances, &payment.from, zero) += payment.amount;
+        *balance_of(&mut balances, &payment.to, zero) -= payment.amount;
+    }
+
+    balances.retain(|b| !b.amount.is_zero() || nobt.has_participant(&b.name));
+
+    balances
+}
+
+fn balance_of<'b>(balances: &'b mut Vec<Balance>, name: &str, zero: Money) -> &'b mut Money {
+    let index = match balances.iter().position(|b| b.name == name) {
+        Some(index) => index,
+        None => {
+            balances.push(Balance {
+                name: name.to_owned(),
+                amount: zero,
+            });
+            balances.len() - 1
+        }
+    };
+
+    &mut balances[index].amount
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+    use crate::nobt::{Bill, Debtee, Participant, Payment};
+    use time::OffsetDateTime;
+
+    #[test]
+    fn balances_sum_up_to_zero() {
+        let nobt = nobt(vec![Bill::for_test(
+            1,
+            1000,
+            "Thomas",
+            &["Thomas", "Simon", "Prada"],
+        )]);
+
+        let balances = amounts(&nobt);
+
+        assert_eq!(balances, vec![667, -334, -333]);
+        assert_eq!(balances.iter().sum::<i64>(), 0);
+    }
+
+    #[test]
+    fn deleted_bills_are_ignored() {
+        let mut deleted = Bill::for_test(2, 15000, "Simon", &["Thomas"]);
+        deleted.deleted = true;
+        let nobt = nobt(vec![
+            Bill::for_test(1, 3900, "Thomas", &["Thomas", "Simon"]),
+            deleted,
+        ]);
+
+        assert_eq!(amounts(&nobt), vec![1950, -1950, 0]);
+    }
+
+    #[test]
+    fn every_debtee_gets_back_what_they_paid() {
+        let mut bill = Bill::for_test(1, 9000, "Thomas", &["Thomas", "Simon", "Prada"]);
+        bill.debtees = vec![
+            Debtee {
+                name: "Thomas".to_owned(),
+                amount: Money::new(6000, Currency::EUR),
+            },
+            Debtee {
+                name: "Simon".to_owned(),
+                amount: Money::new(3000, Currency::EUR),
+            },
+        ];
+
+        assert_eq!(amounts(&nobt(vec![bill])), vec![3000, 0, -3000]);
+    }
+
+    #[test]
+    fn replaced_revisions_are_ignored() {
+        let mut replaced = Bill::for_test(1, 15000, "Simon", &["Thomas"]);
+        replaced.replaced_by = Some(2);
+        let nobt = nobt(vec![
+            replaced,
+            Bill::for_test(2, 3900, "Thomas", &["Thomas", "Simon"]),
+        ]);
+
+        assert_eq!(amounts(&nobt), vec![1950, -1950, 0]);
+    }
+
+    #[test]
+    fn removed_participants_are_only_listed_with_open_balance() {
+        let mut nobt = nobt(vec![Bill::for_test(1, 3900, "Benji", &["Benji", "Thomas"])]);
+
+        assert_eq!(balances(&nobt).len(), 4);
+
+        nobt.bills[0].deleted = true;
+        assert_eq!(
+            balances(&nobt)
+                .into_iter()
+                .map(|b| b.name)
+                .collect::<Vec<_>>(),
+            vec!["Thomas", "Simon", "Prada"]
+        );
+    }
+
+    #[test]
+    fn payments_reduce_debts() {
+        let mut nobt = nobt(vec![Bill::for_test(
+            1,
+            3900,
+            "Thomas",
+            &["Thomas", "Simon"],
+        )]);
+        nobt.payments.push(Payment {
+            from: "Simon".to_owned(),
+            to: "Thomas".to_owned(),
+            amount: Money::new(1500, Currency::EUR),
+            note: None,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+        });
+
+        assert_eq!(amounts(&nobt), vec![450, -450, 0]);
+    }
+
+    /// The balances of Thomas, Simon and Prada in cents, in that order.
+    fn amounts(nobt: &Nobt) -> Vec<i64> {
+        balances(nobt)
+            .into_iter()
+            .map(|b| b.amount.minor_units())
+            .collect()
+    }
+
+    fn nobt(bills: Vec<Bill>) -> Nobt {
+        Nobt {
+            id: "abc".to_owned(),
+            title: "Swedish Shenanigans".to_owned(),
+            currency: Currency::EUR,
+            participants: ["Thomas", "Simon", "Prada"]
+                .into_iter()
+                .map(|name| Participant {
+                    name: name.to_owned(),
+                })
+                .collect(),
+            bills,
+            payments: Vec::new(),
+        }
+    }
+}
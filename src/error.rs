@@ -0,0 +1,168 @@
+use crate::components::Head;
+use crate::i18n::t;
+use crate::Header;
+use axum::body::{boxed, Full};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use rscx::html;
+
+/// Everything that can go wrong while handling a request.
+///
+/// Each error is answered with the appropriate status code and a branded error page, see [`render_error_pages`].
+#[derive(Debug)]
+pub enum AppError {
+    NobtNotFound,
+    BillNotFound,
+    ParticipantNotFound,
+    /// The request doesn't make sense, the message tells the user why.
+    BadRequest(&'static str),
+    /// Something failed on our side, e.g. the database.
+    Internal(anyhow::Error),
+}
+
+impl<E> From<E> for AppError
+where
+    E: Into<anyhow::Error>,
+{
+    fn from(e: E) -> Self {
+        Self::Internal(e.into())
+    }
+}
+
+/// The message shown on the error page, attached to the response by [`AppError`].
+#[derive(Clone, Copy, Debug)]
+struct ErrorMessage(&'static str);
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            AppError::NobtNotFound => (
+                StatusCode::NOT_FOUND,
+                "We looked everywhere but couldn't find this nobt.",
+            ),
+            AppError::BillNotFound => (
+                StatusCode::NOT_FOUND,
+                "We looked everywhere but couldn't find this bill.",
+            ),
+            AppError::ParticipantNotFound => (
+                StatusCode::NOT_FOUND,
+                "We looked everywhere but couldn't find this person.",
+            ),
+            AppError::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            AppError::Internal(e) => {
+                eprintln!("Failed to handle request: {e:#}");
+
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    default_message(StatusCode::INTERNAL_SERVER_ERROR),
+                )
+            }
+        };
+
+        let mut response = status.into_response();
+        response.extensions_mut().insert(ErrorMessage(message));
+
+        response
+    }
+}
+
+/// Turns every error response that isn't HTML already into a branded error page, keeping its status code.
+///
+/// Besides our own [`AppError`]s, this covers the rejections of axum's extractors (e.g. a bill ID that is not a number)
+/// and requests to routes that don't exist. Error responses that are HTML already, like forms that failed validation,
+/// are left alone.
+pub async fn render_error_pages(response: Response) -> Response {
+    let status = response.status();
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/html"));
+    if !(status.is_client_error() || status.is_server_error()) || is_html {
+        return response;
+    }
+
+    let message = response
+        .extensions()
+        .get::<ErrorMessage>()
+        .map(|message| message.0)
+        .unwrap_or_else(|| default_message(status));
+    let page = error_page(status, message).await;
+
+    let (mut parts, _) = response.into_parts();
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("text/html; charset=utf-8"),
+    );
+    parts.headers.remove(header::CONTENT_LENGTH);
+
+    Response::from_parts(parts, boxed(Full::from(page)))
+}
+
+fn default_message(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::NOT_FOUND => "We looked everywhere but couldn't find this page.",
+        status if status.is_server_error() => {
+            "Something went wrong on our side. Please try again in a moment."
+        }
+        _ => "We couldn't make sense of this request.",
+    }
+}
+
+async fn error_page(status: StatusCode, message: &'static str) -> String {
+    let title = t(status.canonical_reason().unwrap_or("Error"));
+
+    html! {
+        <>
+            <!DOCTYPE html>
+            <Head title=title />
+            <body hx-boost="true" class="bg-turquoise sm:bg-lightGrey h-screen">
+                <div class="sm:pt-10">
+                    <div class="bg-turquoise container mx-auto sm:shadow-lg sm:rounded-lg max-w-3xl">
+                        <Header>
+                            <h1 class="text-xl">"nobt.io"</h1>
+                        </Header>
+                        <div class="p-12 flex flex-col gap-4 items-center">
+                            // <div class="bg-cover h-80 w-2/3 bg-center bg-[url('/not_found.jpg')]">""</div>
+
+                            <h2 class="text-lg w-72 text-center text-white">{t(message)}</h2>
+
+                            <a class="bg-white rounded-md px-4 py-2 shadow" href="/create">
+                                {t("Create a new nobt")}
+                            </a>
+                        </div>
+                    </div>
+                </div>
+            </body>
+        </>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::HttpBody;
+    use axum::response::Html;
+
+    #[tokio::test]
+    async fn errors_become_html_pages_with_their_status() {
+        let response = render_error_pages(AppError::BillNotFound.into_response()).await;
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+    }
+
+    #[tokio::test]
+    async fn html_error_responses_are_kept() {
+        let response = (StatusCode::UNPROCESSABLE_ENTITY, Html("<form></form>")).into_response();
+
+        let response = render_error_pages(response).await;
+
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body = response.into_body().data().await.unwrap().unwrap();
+        assert_eq!(body, "<form></form>");
+    }
+}
@@ -0,0 +1,296 @@
+use crate::money::Money;
+use axum::headers::{Cookie, HeaderMapExt};
+use axum::http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, VARY};
+use axum::http::{HeaderValue, Request};
+use axum::middleware::Next;
+use axum::response::Response;
+use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+/// The locales the UI is translated into and amounts and dates are formatted for.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+}
+
+tokio::task_local! {
+    pub(crate) static LOCALE: Locale;
+}
+
+impl Locale {
+    pub const ALL: &'static [Locale] = &[Locale::En, Locale::De];
+
+    /// The language subtag of this locale, as used in `Accept-Language`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+        }
+    }
+
+    /// The locale with the given language subtag, e.g. `de`.
+    pub fn from_code(code: &str) -> Option<Locale> {
+        Locale::ALL
+            .iter()
+            .copied()
+            .find(|locale| locale.code().eq_ignore_ascii_case(code))
+    }
+
+    /// Picks the supported locale the client prefers most from an `Accept-Language` header like `de-CH, en;q=0.8`.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut best = None::<(Locale, f32)>;
+
+        for entry in header.split(',') {
+            let mut parts = entry.split(';');
+            let language = parts
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .split('-')
+                .next()
+                .unwrap_or_default();
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())
+                .unwrap_or(0.0);
+
+            let Some(locale) = Locale::from_code(language) else {
+                continue;
+            };
+            if quality > 0.0 && best.is_none_or(|(_, best)| quality > best) {
+                best = Some((locale, quality));
+            }
+        }
+
+        best.map(|(locale, _)| locale)
+    }
+
+    /// The locale of the request that is currently being handled, English outside of requests.
+    pub fn current() -> Locale {
+        LOCALE.try_with(|locale| *locale).unwrap_or_default()
+    }
+}
+
+/// The cookie that stores the language picked in the language switcher.
+pub const LANGUAGE_COOKIE: &str = "lang";
+
+/// Middleware that makes the locale of a request available through [`Locale::current`] while it is handled.
+///
+/// A language picked in the language switcher takes precedence over the `Accept-Language` header of the browser.
+pub async fn with_request_locale<B>(request: Request<B>, next: Next<B>) -> Response {
+    let headers = request.headers();
+    let picked = headers
+        .typed_get::<Cookie>()
+        .and_then(|cookie| cookie.get(LANGUAGE_COOKIE).and_then(Locale::from_code));
+    let locale = picked
+        .or_else(|| {
+            headers
+                .get(ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(Locale::from_accept_language)
+        })
+        .unwrap_or_default();
+
+    let mut response = LOCALE.scope(locale, next.run(request)).await;
+    let headers = response.headers_mut();
+    headers.insert(CONTENT_LANGUAGE, HeaderValue::from_static(locale.code()));
+    headers.append(VARY, HeaderValue::from_static("Accept-Language, Cookie"));
+
+    response
+}
+
+/// Formats an amount with its currency symbol, e.g. `€1,234.50` in English or `1.234,50 €` in German.
+pub fn amount(value: Money, locale: Locale) -> String {
+    let sign = if value.is_negative() { "-" } else { "" };
+    let symbol = value.currency().symbol();
+    let decimal = value.abs().to_decimal_string();
+    let (major, minor) = match decimal.split_once('.') {
+        Some((major, minor)) => (major, Some(minor)),
+        None => (decimal.as_str(), None),
+    };
+    let (group_separator, decimal_separator) = match locale {
+        Locale::En => (',', '.'),
+        Locale::De => ('.', ','),
+    };
+
+    let mut number = group_digits(major, group_separator);
+    if let Some(minor) = minor {
+        number.push(decimal_separator);
+        number.push_str(minor);
+    }
+
+    match locale {
+        // Symbols made of letters are separated from the amount by a space.
+        Locale::En if symbol.ends_with(char::is_alphabetic) => format!("{sign}{symbol} {number}"),
+        Locale::En => format!("{sign}{symbol}{number}"),
+        Locale::De => format!("{sign}{number} {symbol}"),
+    }
+}
+
+/// Formats a date with the month written out, e.g. `28 August 2022` in English or `28. August 2022` in German.
+pub fn date(date: Date, locale: Locale) -> String {
+    let (day, year) = (date.day(), date.year());
+    let month = month_name(date.month(), locale);
+
+    match locale {
+        Locale::En => format!("{day} {month} {year}"),
+        Locale::De => format!("{day}. {month} {year}"),
+    }
+}
+
+/// Formats a point in time as its date and UTC time of day, e.g. `28 August 2022, 14:05 UTC`.
+pub fn date_time(date_time: OffsetDateTime, locale: Locale) -> String {
+    let date_time = date_time.to_offset(UtcOffset::UTC);
+    let (hour, minute) = (date_time.hour(), date_time.minute());
+
+    format!(
+        "{}, {hour:02}:{minute:02} UTC",
+        date(date_time.date(), locale)
+    )
+}
+
+/// Parses a date in the ISO 8601 format `2022-08-28`, as sent by date inputs and stored in the database.
+pub fn parse_iso_date(text: &str) -> Option<Date> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse::<u8>().ok()?;
+    let day = parts.next()?.parse().ok()?;
+
+    Date::from_calendar_date(year, Month::try_from(month).ok()?, day).ok()
+}
+
+/// Percent-encodes text for use as a single segment of a URL path, e.g. the name in `/{nobt_id}/balances/{name}`.
+pub fn path_segment(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(b).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn month_name(month: Month, locale: Locale) -> &'static str {
+    const EN: [&str; 12] = [
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ];
+    const DE: [&str; 12] = [
+        "Januar",
+        "Februar",
+        "März",
+        "April",
+        "Mai",
+        "Juni",
+        "Juli",
+        "August",
+        "September",
+        "Oktober",
+        "November",
+        "Dezember",
+    ];
+
+    let index = u8::from(month) as usize - 1;
+    match locale {
+        Locale::En => EN[index],
+        Locale::De => DE[index],
+    }
+}
+
+/// Inserts a separator between every group of three digits, counted from the right.
+fn group_digits(digits: &str, separator: char) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+
+    #[test]
+    fn amounts_follow_the_locale() {
+        let eur = Money::new(123450, Currency::EUR);
+        let chf = Money::new(-1234, Currency::CHF);
+
+        assert_eq!(amount(eur, Locale::En), "€1,234.50");
+        assert_eq!(amount(eur, Locale::De), "1.234,50 €");
+        assert_eq!(amount(chf, Locale::En), "-CHF 12.34");
+        assert_eq!(amount(chf, Locale::De), "-12,34 CHF");
+        assert_eq!(
+            amount(Money::new(1500000, Currency::JPY), Locale::De),
+            "1.500.000 ¥"
+        );
+    }
+
+    #[test]
+    fn dates_follow_the_locale() {
+        let date = Date::from_calendar_date(2022, Month::March, 28).unwrap();
+
+        assert_eq!(super::date(date, Locale::En), "28 March 2022");
+        assert_eq!(super::date(date, Locale::De), "28. März 2022");
+
+        let date_time = date.with_hms(14, 5, 0).unwrap().assume_utc();
+        assert_eq!(
+            super::date_time(date_time, Locale::De),
+            "28. März 2022, 14:05 UTC"
+        );
+    }
+
+    #[test]
+    fn iso_dates_are_parsed() {
+        assert_eq!(
+            parse_iso_date("2022-08-28"),
+            Some(Date::from_calendar_date(2022, Month::August, 28).unwrap())
+        );
+        assert_eq!(parse_iso_date("2022-02-30"), None);
+        assert_eq!(parse_iso_date("28.08.2022"), None);
+        assert_eq!(parse_iso_date(""), None);
+    }
+
+    #[test]
+    fn accept_language_picks_the_preferred_supported_locale() {
+        assert_eq!(
+            Locale::from_accept_language("de-CH, de;q=0.9, en;q=0.8"),
+            Some(Locale::De)
+        );
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, en;q=0.5, de;q=0.7"),
+            Some(Locale::De)
+        );
+        assert_eq!(
+            Locale::from_accept_language("en-US,en;q=0.9"),
+            Some(Locale::En)
+        );
+        assert_eq!(Locale::from_accept_language("de;q=0, fr"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+    }
+
+    #[test]
+    fn path_segments_are_percent_encoded() {
+        assert_eq!(path_segment("Thomas"), "Thomas");
+        assert_eq!(path_segment("Anna Lena"), "Anna%20Lena");
+        assert_eq!(path_segment("a/b?c#d"), "a%2Fb%3Fc%23d");
+        assert_eq!(path_segment("Jürgen"), "J%C3%BCrgen");
+    }
+}
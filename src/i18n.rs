@@ -0,0 +1,384 @@
+use crate::format::{Locale, LANGUAGE_COOKIE};
+use crate::responses::SeeOther;
+use axum::extract::Path;
+use axum::http::header::{REFERER, SET_COOKIE};
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use std::fmt::Display;
+
+/// Translates a text of the UI into the language of the current request.
+///
+/// The English text is the key into the catalog of each language. Texts that aren't translated yet are shown in English.
+pub fn t(text: &str) -> &str {
+    let catalog = match Locale::current() {
+        Locale::En => return text,
+        Locale::De => DE,
+    };
+
+    catalog
+        .iter()
+        .find(|(key, _)| *key == text)
+        .map_or(text, |(_, translation)| translation)
+}
+
+/// Translates a text with placeholders like `{name}` and fills them in with the given arguments.
+///
+/// The placeholders are filled in a single pass over the translated text, so braces in the arguments (which are often
+/// entered by users) are never taken for placeholders.
+pub fn tf(text: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let mut filled = String::new();
+    let mut rest = t(text);
+
+    while let Some((before, after)) = rest.split_once('{') {
+        let Some((name, after)) = after.split_once('}') else {
+            break;
+        };
+        filled.push_str(before);
+
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => filled.push_str(&value.to_string()),
+            None => filled.push_str(&format!("{{{name}}}")),
+        }
+        rest = after;
+    }
+    filled.push_str(rest);
+
+    filled
+}
+
+/// Remembers the language picked in the language switcher and takes the user back to the page they came from.
+pub async fn switch_language(Path(code): Path<String>, headers: HeaderMap) -> Response {
+    let Some(locale) = Locale::from_code(&code) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    // Only the path of the referring page is used, so we never redirect to another site.
+    let back = headers
+        .get(REFERER)
+        .and_then(|referer| referer.to_str().ok())
+        .and_then(|referer| referer.parse::<Uri>().ok())
+        .and_then(|uri| uri.path_and_query().map(|path| path.to_string()))
+        .filter(|path| path.starts_with('/') && !path.starts_with("//"))
+        .unwrap_or_else(|| "/".to_owned());
+    let cookie = format!(
+        "{LANGUAGE_COOKIE}={}; Path=/; Max-Age=31536000; SameSite=Lax",
+        locale.code()
+    );
+
+    ([(SET_COOKIE, cookie)], SeeOther(back)).into_response()
+}
+
+/// The German translations of the UI.
+static DE: &[(&str, &str)] = &[
+    // Landing page
+    ("nobt.io: Split your bills with ease", "nobt.io: Teilt eure Rechnungen ganz einfach"),
+    ("Nobt.io is a free service to split bills among your friends. It is super simple and ease to use. Create a nobt, share the link with your friends and start splitting bills.", "Nobt.io ist ein kostenloser Dienst, um Rechnungen unter Freunden aufzuteilen. Er ist super einfach zu bedienen. Erstellt einen Nobt, teilt den Link mit euren Freunden und legt los."),
+    ("About", "Über uns"),
+    ("Features", "Funktionen"),
+    ("Team", "Team"),
+    ("Language", "Sprache"),
+    ("split your bills", "teilt eure Rechnungen"),
+    ("with ease", "ganz einfach"),
+    ("Get started - Create a Nobt", "Jetzt loslegen - Nobt erstellen"),
+    ("You will", "Ihr werdet"),
+    ("Nobt.io is a free service that solves the tedious task of splitting several bills among your friends with ease.", "Nobt.io ist ein kostenloser Dienst, der euch das mühsame Aufteilen vieler Rechnungen unter Freunden abnimmt."),
+    ("Try it for your holiday.", "Probiert es im nächsten Urlaub aus."),
+    ("Easy to Use", "Einfach zu bedienen"),
+    ("Nobt.io works without registration. Simply create a nobt and share the link with your friends.", "Nobt.io funktioniert ohne Registrierung. Erstellt einfach einen Nobt und teilt den Link mit euren Freunden."),
+    ("Available Everywhere", "Überall verfügbar"),
+    ("Nobts are stored in the cloud, so you can access them from any device, anytime, no matter where you are.", "Nobts werden in der Cloud gespeichert, ihr könnt also jederzeit von jedem Gerät darauf zugreifen, egal wo ihr seid."),
+    ("Open Source", "Open Source"),
+    ("We believe that the Web should be open that's why we share everything about nobt.io, except your data.", "Wir glauben an ein offenes Web, deshalb teilen wir alles über nobt.io, außer euren Daten."),
+    ("The team behind nobt.io.", "Das Team hinter nobt.io."),
+    ("Crafted with", "Gemacht mit"),
+    ("in Vienna and Sydney.", "in Wien und Sydney."),
+    ("What started as a hackathon by three motivated developers soon turned out to be an actually helpful companion in our daily life. Nobt.io is our effort to share this idea with all of you. We hope you enjoy it as much as we do.", "Was als Hackathon dreier motivierter Entwickler begann, wurde bald zu einem wirklich hilfreichen Begleiter in unserem Alltag. Mit Nobt.io wollen wir diese Idee mit euch allen teilen. Wir hoffen, ihr habt genauso viel Freude daran wie wir."),
+    ("Contact Us", "Kontakt"),
+    // Error pages
+    ("We looked everywhere but couldn't find this nobt.", "Wir haben überall gesucht, aber diesen Nobt nicht gefunden."),
+    ("We looked everywhere but couldn't find this bill.", "Wir haben überall gesucht, aber diese Rechnung nicht gefunden."),
+    ("We looked everywhere but couldn't find this person.", "Wir haben überall gesucht, aber diese Person nicht gefunden."),
+    ("We looked everywhere but couldn't find this page.", "Wir haben überall gesucht, aber diese Seite nicht gefunden."),
+    ("Something went wrong on our side. Please try again in a moment.", "Bei uns ist etwas schiefgelaufen. Bitte versucht es gleich noch einmal."),
+    ("We couldn't make sense of this request.", "Mit dieser Anfrage konnten wir nichts anfangen."),
+    ("Create a new nobt", "Einen neuen Nobt erstellen"),
+    ("Error", "Fehler"),
+    ("Bad Request", "Ungültige Anfrage"),
+    ("Not Found", "Nicht gefunden"),
+    ("Method Not Allowed", "Methode nicht erlaubt"),
+    ("Unprocessable Entity", "Ungültige Eingabe"),
+    ("Internal Server Error", "Interner Serverfehler"),
+    ("This field doesn't exist.", "Dieses Feld gibt es nicht."),
+    // Creating a nobt
+    ("Create a nobt", "Nobt erstellen"),
+    ("What are you planning?", "Was habt ihr vor?"),
+    ("Road Trip, Summer Holidays, Flat Share, ...", "Roadtrip, Sommerurlaub, WG, ..."),
+    ("Give your nobt a name so everyone recognizes it.", "Gebt eurem Nobt einen Namen, damit alle ihn wiedererkennen."),
+    ("Which currency do you use?", "Welche Währung verwendet ihr?"),
+    ("All bills of this nobt are entered in this currency.", "Alle Rechnungen dieses Nobts werden in dieser Währung erfasst."),
+    ("Who is in?", "Wer ist dabei?"),
+    ("Separate names with a comma. You can always add more people later.", "Trennt die Namen mit einem Komma. Weitere Personen könnt ihr jederzeit später hinzufügen."),
+    ("Create nobt", "Nobt erstellen"),
+    ("The nobt needs a name.", "Der Nobt braucht einen Namen."),
+    ("Names of nobts must not be longer than 60 characters.", "Namen von Nobts dürfen nicht länger als 60 Zeichen sein."),
+    ("The currency is not supported.", "Diese Währung wird nicht unterstützt."),
+    // The nobt
+    ("Show balances", "Salden anzeigen"),
+    ("Settle up", "Abrechnen"),
+    ("Pay someone", "Jemanden bezahlen"),
+    ("Add a bill", "Rechnung hinzufügen"),
+    ("{debtees} paid '{bill}'", "{debtees} hat '{bill}' bezahlt"),
+    ("{from} paid {to} '{note}'", "{from} hat {to} bezahlt '{note}'"),
+    ("{from} paid {to}", "{from} hat {to} bezahlt"),
+    ("Today", "Heute"),
+    ("Yesterday", "Gestern"),
+    ("Spent per category", "Ausgaben pro Kategorie"),
+    ("Share this code with your friends so they can open the nobt.", "Teilt diesen Code mit euren Freunden, damit sie den Nobt öffnen können."),
+    // The bill wizard
+    ("Edit bill", "Rechnung bearbeiten"),
+    ("Save changes", "Änderungen speichern"),
+    ("Add bill", "Rechnung hinzufügen"),
+    ("What did you buy?", "Was habt ihr gekauft?"),
+    ("Trip Snacks, Train Tickets, Beer, ...", "Reiseproviant, Zugtickets, Bier, ..."),
+    ("Enter a descriptive name for what was paid for.", "Gebt einen aussagekräftigen Namen für das Bezahlte ein."),
+    ("When was it paid?", "Wann wurde bezahlt?"),
+    ("Enter the day the bill was paid on, it defaults to today.", "Gebt den Tag ein, an dem die Rechnung bezahlt wurde, standardmäßig heute."),
+    ("Enter the day the bill was paid on.", "Gebt den Tag ein, an dem die Rechnung bezahlt wurde."),
+    ("What kind of expense is it?", "Was für eine Ausgabe ist es?"),
+    ("Skiing, Souvenirs, Fuel, ...", "Skifahren, Souvenirs, Tanken, ..."),
+    ("Pick a category or name a new one, which is used instead.", "Wählt eine Kategorie aus oder benennt eine neue, die stattdessen verwendet wird."),
+    ("Categories must not be empty.", "Kategorien dürfen nicht leer sein."),
+    ("Categories must not be longer than 30 characters.", "Kategorien dürfen nicht länger als 30 Zeichen sein."),
+    ("Food", "Essen"),
+    ("Transport", "Transport"),
+    ("Accommodation", "Unterkunft"),
+    ("Drinks", "Getränke"),
+    ("Activities", "Aktivitäten"),
+    ("Other", "Sonstiges"),
+    ("How much did it cost?", "Wie viel hat es gekostet?"),
+    ("Enter the total of this bill and the currency it was paid in.", "Gebt den Gesamtbetrag der Rechnung und die Währung ein, in der bezahlt wurde."),
+    ("The balances are computed in the currency of this nobt, using this exchange rate.", "Die Salden werden mit diesem Wechselkurs in der Währung dieses Nobts berechnet."),
+    ("Who paid?", "Wer hat bezahlt?"),
+    ("{name} paid the bill.", "{name} hat die Rechnung bezahlt."),
+    ("Select a Debtee", "Zahler auswählen"),
+    ("Select the person or persons who paid this bill.", "Wählt die Person oder Personen aus, die diese Rechnung bezahlt haben."),
+    ("Who is involved?", "Wer ist beteiligt?"),
+    ("Nobody is involved", "Niemand ist beteiligt"),
+    ("1 person is involved", "1 Person ist beteiligt"),
+    ("{num} persons are involved.", "{num} Personen sind beteiligt."),
+    ("Select who is involved in this bill.", "Wählt aus, wer an dieser Rechnung beteiligt ist."),
+    ("How is it split?", "Wie wird aufgeteilt?"),
+    ("Split equally.", "Zu gleichen Teilen aufgeteilt."),
+    ("Split by shares.", "Nach Anteilen aufgeteilt."),
+    ("Split by percentages.", "Nach Prozenten aufgeteilt."),
+    ("Split by exact amounts.", "Nach genauen Beträgen aufgeteilt."),
+    ("Select how the total is divided between the people involved.", "Wählt aus, wie der Gesamtbetrag unter den Beteiligten aufgeteilt wird."),
+    ("Select debtees", "Zahler auswählen"),
+    ("Someone else?", "Jemand anderes?"),
+    ("Add", "Hinzufügen"),
+    ("They will be added to the participants of this nobt.", "Die Person wird zu den Teilnehmern dieses Nobts hinzugefügt."),
+    ("Did several people pay?", "Haben mehrere Personen bezahlt?"),
+    ("Enter how much each person paid. The amounts need to add up to the total of the bill.", "Gebt ein, wie viel jede Person bezahlt hat. Die Beträge müssen zusammen den Gesamtbetrag der Rechnung ergeben."),
+    ("Set debtees", "Zahler übernehmen"),
+    ("Select debtors", "Beteiligte auswählen"),
+    ("Set debtors", "Beteiligte übernehmen"),
+    ("Split the bill", "Rechnung aufteilen"),
+    ("Equally", "Zu gleichen Teilen"),
+    ("By shares", "Nach Anteilen"),
+    ("By percentages", "Nach Prozenten"),
+    ("By exact amounts", "Nach genauen Beträgen"),
+    ("Who pays how much?", "Wer zahlt wie viel?"),
+    ("Enter the number of shares, the percentage or the exact amount of each person. Nothing needs to be entered when splitting equally.", "Gebt für jede Person die Anzahl der Anteile, den Prozentsatz oder den genauen Betrag ein. Bei einer Aufteilung zu gleichen Teilen ist keine Eingabe nötig."),
+    ("Set split", "Aufteilung übernehmen"),
+    ("The bill needs a name.", "Die Rechnung braucht einen Namen."),
+    ("The total of a bill must be a positive amount.", "Der Gesamtbetrag einer Rechnung muss positiv sein."),
+    ("This amount is too large.", "Dieser Betrag ist zu groß."),
+    ("Choose one of the listed currencies.", "Wählt eine der aufgeführten Währungen."),
+    ("Enter the exchange rate of the currency the bill was paid in.", "Gebt den Wechselkurs der Währung ein, in der die Rechnung bezahlt wurde."),
+    ("The exchange rate must be a positive number with at most six decimal places.", "Der Wechselkurs muss eine positive Zahl mit höchstens sechs Nachkommastellen sein."),
+    ("Paid amounts must be positive.", "Bezahlte Beträge müssen positiv sein."),
+    ("Someone needs to have paid the bill.", "Jemand muss die Rechnung bezahlt haben."),
+    ("The paid amounts must add up to the total of the bill.", "Die bezahlten Beträge müssen zusammen den Gesamtbetrag der Rechnung ergeben."),
+    ("Choose how the bill is split.", "Wählt aus, wie die Rechnung aufgeteilt wird."),
+    ("Enter a value for everyone involved in the bill.", "Gebt für alle Beteiligten einen Wert ein."),
+    ("Shares must be whole numbers greater than zero.", "Anteile müssen ganze Zahlen größer als null sein."),
+    ("Percentages must be between 0 and 100 with at most two decimal places.", "Prozentsätze müssen zwischen 0 und 100 liegen und dürfen höchstens zwei Nachkommastellen haben."),
+    ("The percentages must add up to 100%.", "Die Prozentsätze müssen zusammen 100 % ergeben."),
+    ("Amounts must not be negative.", "Beträge dürfen nicht negativ sein."),
+    ("Enter a valid amount for everyone involved in the bill.", "Gebt für alle Beteiligten einen gültigen Betrag ein."),
+    ("The amounts must add up to the total of the bill.", "Die Beträge müssen zusammen den Gesamtbetrag der Rechnung ergeben."),
+    ("At least one person needs to be involved in the bill.", "Mindestens eine Person muss an der Rechnung beteiligt sein."),
+    ("Only participants of this nobt can be part of a bill.", "Nur Teilnehmer dieses Nobts können an einer Rechnung beteiligt sein."),
+    ("Names must not be empty.", "Namen dürfen nicht leer sein."),
+    ("Names must not be longer than 40 characters.", "Namen dürfen nicht länger als 40 Zeichen sein."),
+    ("Names must not contain control characters.", "Namen dürfen keine Steuerzeichen enthalten."),
+    // Balances
+    ("Balances", "Salden"),
+    ("Balance overview", "Übersicht der Salden"),
+    ("The balances of all users in this Nobt.", "Die Salden aller Teilnehmer dieses Nobts."),
+    ("Summary", "Zusammenfassung"),
+    ("Debts", "Schulden"),
+    ("{name}'s balance", "Saldo von {name}"),
+    ("{name} paid 1 bill ({amount}).", "{name} hat 1 Rechnung bezahlt ({amount})."),
+    ("{name} paid {num} bills ({amount}).", "{name} hat {num} Rechnungen bezahlt ({amount})."),
+    ("{name} participates in {num} of {total} bills.", "{name} ist an {num} von {total} Rechnungen beteiligt."),
+    ("{name} owes {amount} to 1 person.", "{name} schuldet 1 Person {amount}."),
+    ("{name} owes {amount} to {num} persons.", "{name} schuldet {num} Personen {amount}."),
+    ("{name} gets {amount} from 1 person.", "{name} bekommt {amount} von 1 Person."),
+    ("{name} gets {amount} from {num} persons.", "{name} bekommt {amount} von {num} Personen."),
+    ("{name} is settled up.", "{name} ist ausgeglichen."),
+    // Settling up
+    ("How to settle up", "So rechnet ihr ab"),
+    ("Everyone is settled up.", "Alle sind ausgeglichen."),
+    ("1 transfer settles all debts in this Nobt.", "1 Überweisung begleicht alle Schulden in diesem Nobt."),
+    ("{num} transfers settle all debts in this Nobt.", "{num} Überweisungen begleichen alle Schulden in diesem Nobt."),
+    ("{from} pays {to}", "{from} zahlt an {to}"),
+    // Participants
+    ("Participants", "Teilnehmer"),
+    ("Everyone who shares bills in this Nobt. Participants can only be removed once they are settled up.", "Alle, die sich in diesem Nobt Rechnungen teilen. Teilnehmer können erst entfernt werden, wenn sie ausgeglichen sind."),
+    ("New name of {name}", "Neuer Name von {name}"),
+    ("Rename", "Umbenennen"),
+    ("Remove", "Entfernen"),
+    ("Remove {name} from this nobt?", "{name} aus diesem Nobt entfernen?"),
+    ("Someone missing?", "Fehlt jemand?"),
+    ("Same person twice?", "Dieselbe Person doppelt?"),
+    ("Merge participants who were added under different names. You can check the changes before merging.", "Führt Teilnehmer zusammen, die unter verschiedenen Namen hinzugefügt wurden. Vor dem Zusammenführen könnt ihr die Änderungen prüfen."),
+    ("Merge", "Zusammenführen"),
+    ("into", "mit"),
+    ("Preview merge", "Vorschau anzeigen"),
+    ("Bills or payments of a former participant use this name, pick another one.", "Rechnungen oder Zahlungen von jemandem, der nicht mehr dabei ist, verwenden diesen Namen. Wählt einen anderen."),
+    ("Someone with this name is already part of this nobt.", "Jemand mit diesem Namen ist bereits Teil dieses Nobts."),
+    ("Only participants who are settled up can be removed.", "Nur ausgeglichene Teilnehmer können entfernt werden."),
+    ("A participant cannot be merged into themselves.", "Ein Teilnehmer kann nicht mit sich selbst zusammengeführt werden."),
+    ("Merge participants", "Teilnehmer zusammenführen"),
+    ("Merge {name} into {into}?", "{name} mit {into} zusammenführen?"),
+    ("{into} takes over {bills} and {payments} of {name}. Payments between the two are dropped.", "{into} übernimmt {bills} und {payments} von {name}. Zahlungen zwischen den beiden entfallen."),
+    ("1 bill", "1 Rechnung"),
+    ("{num} bills", "{num} Rechnungen"),
+    ("1 payment", "1 Zahlung"),
+    ("{num} payments", "{num} Zahlungen"),
+    ("Nobody's balance changes.", "Kein Saldo ändert sich."),
+    ("merged into {into}", "mit {into} zusammengeführt"),
+    // Payments
+    ("Select the person who sent the money.", "Wählt die Person aus, die das Geld geschickt hat."),
+    ("Who received the money?", "Wer hat das Geld bekommen?"),
+    ("Select the person who got the money.", "Wählt die Person aus, die das Geld bekommen hat."),
+    ("How much was paid?", "Wie viel wurde bezahlt?"),
+    ("Enter the amount that was transferred.", "Gebt den überwiesenen Betrag ein."),
+    ("What was it for?", "Wofür war es?"),
+    ("Settling up, Cash for the taxi, ...", "Abrechnung, Bargeld fürs Taxi, ..."),
+    ("Optionally add a note to this payment.", "Fügt der Zahlung optional eine Notiz hinzu."),
+    ("Add payment", "Zahlung hinzufügen"),
+    ("Payments can only be made between participants of the nobt.", "Zahlungen sind nur zwischen Teilnehmern des Nobts möglich."),
+    ("Nobody can pay themselves.", "Niemand kann sich selbst bezahlen."),
+    ("The amount of a payment must be a positive amount.", "Der Betrag einer Zahlung muss positiv sein."),
+    // Bills
+    ("Debtee", "Zahler"),
+    ("{name} paid this bill.", "{name} hat diese Rechnung bezahlt."),
+    ("Spent on {category}.", "Ausgegeben für {category}."),
+    ("Paid on {date}.", "Bezahlt am {date}."),
+    ("Added on {date}.", "Hinzugefügt am {date}."),
+    ("The invoice total is {total}.", "Der Rechnungsbetrag ist {total}."),
+    ("Converted at {rate}, this makes {total}.", "Umgerechnet zu {rate} ergibt das {total}."),
+    ("It is split equally.", "Sie wird zu gleichen Teilen aufgeteilt."),
+    ("It is split by shares.", "Sie wird nach Anteilen aufgeteilt."),
+    ("It is split by percentages.", "Sie wird nach Prozenten aufgeteilt."),
+    ("It is split by exact amounts.", "Sie wird nach genauen Beträgen aufgeteilt."),
+    ("Debtors", "Beteiligte"),
+    ("Actions", "Aktionen"),
+    ("This bill has been edited, show the latest version", "Diese Rechnung wurde bearbeitet, neueste Version anzeigen"),
+    ("Edit this bill", "Rechnung bearbeiten"),
+    ("Delete this bill", "Rechnung löschen"),
+    ("Deleted bills no longer count towards the balances. Proceed?", "Gelöschte Rechnungen zählen nicht mehr zu den Salden. Fortfahren?"),
+    ("Restore this bill", "Rechnung wiederherstellen"),
+    ("Restored bills count towards the balances again. Proceed?", "Wiederhergestellte Rechnungen zählen wieder zu den Salden. Fortfahren?"),
+    ("History", "Verlauf"),
+    ("Who added and changed this bill.", "Wer diese Rechnung hinzugefügt und geändert hat."),
+    // Activity
+    ("Activity", "Aktivität"),
+    ("Who changed what in this nobt", "Wer was in diesem Nobt geändert hat"),
+    ("Who are you?", "Wer von euch nutzt dieses Gerät?"),
+    ("The changes you make in this nobt are recorded under this name, on this device.", "Eure Änderungen in diesem Nobt werden auf diesem Gerät unter diesem Namen festgehalten."),
+    ("I'd rather not say", "Lieber nicht verraten"),
+    ("Save", "Speichern"),
+    ("Everything that was added, edited, deleted or restored in this nobt.", "Alles, was in diesem Nobt hinzugefügt, bearbeitet, gelöscht oder wiederhergestellt wurde."),
+    ("Nothing has happened here yet.", "Hier ist noch nichts passiert."),
+    ("Someone", "Jemand"),
+    ("{actor} added '{bill}' ({amount})", "{actor} hat '{bill}' hinzugefügt ({amount})"),
+    ("{actor} edited '{bill}' ({amount})", "{actor} hat '{bill}' bearbeitet ({amount})"),
+    ("{actor} deleted '{bill}' ({amount})", "{actor} hat '{bill}' gelöscht ({amount})"),
+    ("{actor} restored '{bill}' ({amount})", "{actor} hat '{bill}' wiederhergestellt ({amount})"),
+    ("{actor} recorded that {from} paid {to} ({amount})", "{actor} hat eingetragen, dass {from} {to} bezahlt hat ({amount})"),
+    ("{actor} added {name}", "{actor} hat {name} hinzugefügt"),
+    ("{actor} renamed {name} to {new_name}", "{actor} hat {name} in {new_name} umbenannt"),
+    ("{actor} removed {name}", "{actor} hat {name} entfernt"),
+    ("{actor} merged {name} into {into}", "{actor} hat {name} mit {into} zusammengeführt"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut placeholders = text
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect::<Vec<_>>();
+        placeholders.sort_unstable();
+        placeholders
+    }
+
+    #[test]
+    fn translations_keep_the_placeholders() {
+        for (text, translation) in DE {
+            assert_eq!(placeholders(text), placeholders(translation), "{text}");
+        }
+    }
+
+    #[test]
+    fn every_text_is_translated_once() {
+        for (index, (text, _)) in DE.iter().enumerate() {
+            assert!(
+                !DE[index + 1..].iter().any(|(other, _)| other == text),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn arguments_are_not_taken_for_placeholders() {
+        assert_eq!(
+            tf(
+                "{from} paid {to}",
+                &[("from", &"{to}"), ("to", &"Thomas {from}")]
+            ),
+            "{to} paid Thomas {from}"
+        );
+        assert_eq!(tf("{name} paid the bill.", &[]), "{name} paid the bill.");
+    }
+
+    #[tokio::test]
+    async fn texts_follow_the_locale_of_the_request() {
+        assert_eq!(t("Settle up"), "Settle up");
+
+        let translated = crate::format::LOCALE
+            .scope(Locale::De, async {
+                (
+                    t("Settle up"),
+                    tf("{name} paid the bill.", &[("name", &"Simon")]),
+                    t("Not in the catalog"),
+                )
+            })
+            .await;
+
+        assert_eq!(
+            translated,
+            (
+                "Abrechnen",
+                "Simon hat die Rechnung bezahlt.".to_owned(),
+                "Not in the catalog"
+            )
+        );
+    }
+}
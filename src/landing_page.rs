@@ -1,6 +1,8 @@
 use axum::response::{Html, IntoResponse};
 use rscx::{component, html, EscapeAttribute};
 use crate::components::Head;
+use crate::i18n::t;
+use crate::LanguageSwitcher;
 
 const NBSP: &str = "\u{00a0}";
 
@@ -9,7 +11,7 @@ pub async fn index() -> impl IntoResponse {
 
     Html(html! {
         <!DOCTYPE html>
-        <Head title="nobt.io: Split your bills with ease" />
+        <Head title={t("nobt.io: Split your bills with ease")} />
         <body hx-boost="true" hx-ext="preload">
             <script>{HEADER_SCROLLED_SCRIPT}</script>
             <header class="bg-transparent fixed top-0 w-full text-white p-5 data-[scrolled=true]:bg-grey">
@@ -19,18 +21,19 @@ pub async fn index() -> impl IntoResponse {
                     </div>
                     <div class="text-right">
                         // TODO: Add icons here
-                        <a class="p-4" href="#about">About</a>
-                        <a class="p-4" href="#features">Features</a>
-                        <a class="p-4" href="#team">Team</a>
+                        <a class="p-4" href="#about">{t("About")}</a>
+                        <a class="p-4" href="#features">{t("Features")}</a>
+                        <a class="p-4" href="#team">{t("Team")}</a>
+                        <LanguageSwitcher classes="inline-flex p-4" />
                     </div>
                 </nav>
             </header>
 
             <section class="h-screen bg-landing-page bg-cover bg-center flex items-center justify-center sm:grid grid-cols-12">
                 <div class="col-span-7 text-right p-10 text-white">
-                    <h1 class="text-4xl">split your bills</h1>
-                    <h2 class="text-3xl font-handWritten mb-10">with ease</h2>
-                    <a class="bg-black/60 hover:bg-darkGreen hover:border-darkGreen px-6 py-3 rounded-full text-md border-white border-2 uppercase text-sm" href="/create">Get started - Create a Nobt</a>
+                    <h1 class="text-4xl">{t("split your bills")}</h1>
+                    <h2 class="text-3xl font-handWritten mb-10">{t("with ease")}</h2>
+                    <a class="bg-black/60 hover:bg-darkGreen hover:border-darkGreen px-6 py-3 rounded-full text-md border-white border-2 uppercase text-sm" href="/create">{t("Get started - Create a Nobt")}</a>
                 </div>
                 <div class="col-span-5">
                     // TODO: Add phone with screenshot here.
@@ -40,12 +43,12 @@ pub async fn index() -> impl IntoResponse {
             <section id="about" class="py-20 bg-white">
                 <div class="container mx-auto px-4 md:w-3/5">
                     <div class="text-center mb-10">
-                        <h2 class="text-3xl">You will{NBSP}<span class="fa-regular fa-heart text-red"></span>{NBSP}nobt.io</h2>
-                        <p class="mt-4">Nobt.io is a free service that solves the tedious task of splitting several bills among your friends with ease.</p>
-                        <p class="mt-2">Try it for your holiday.</p> // TODO: Make this dynamic "typewriter" text.
+                        <h2 class="text-3xl">{t("You will")}{NBSP}<span class="fa-regular fa-heart text-red"></span>{NBSP}nobt.io</h2>
+                        <p class="mt-4">{t("Nobt.io is a free service that solves the tedious task of splitting several bills among your friends with ease.")}</p>
+                        <p class="mt-2">{t("Try it for your holiday.")}</p> // TODO: Make this dynamic "typewriter" text.
                     </div>
                     <div class="flex justify-center mt-6">
-                        <a class="bg-darkGreen px-6 py-3 rounded-full text-md text-white uppercase text-sm" href="/create">Get started - Create a Nobt</a>
+                        <a class="bg-darkGreen px-6 py-3 rounded-full text-md text-white uppercase text-sm" href="/create">{t("Get started - Create a Nobt")}</a>
                     </div>
                 </div>
             </section>
@@ -57,24 +60,24 @@ pub async fn index() -> impl IntoResponse {
                             <div class="flex justify-center mb-4">
                                 <i class="fa-solid fa-gauge-high text-4xl text-black"></i>
                             </div>
-                            <h3 class="text-2xl mb-4">Easy to Use</h3>
-                            <p>"Nobt.io works without registration. Simply create a nobt and share the link with your friends."</p>
+                            <h3 class="text-2xl mb-4">{t("Easy to Use")}</h3>
+                            <p>{t("Nobt.io works without registration. Simply create a nobt and share the link with your friends.")}</p>
                         </div>
 
                         <div class="p-4 text-center">
                             <div class="flex justify-center mb-4">
                                 <i class="fa-solid fa-cloud text-4xl text-black"></i>
                             </div>
-                            <h3 class="text-2xl mb-4">Available Everywhere</h3>
-                            <p>"Nobts are stored in the cloud, so you can access them from any device, anytime, no matter where you are."</p>
+                            <h3 class="text-2xl mb-4">{t("Available Everywhere")}</h3>
+                            <p>{t("Nobts are stored in the cloud, so you can access them from any device, anytime, no matter where you are.")}</p>
                         </div>
 
                         <div class="p-4 text-center">
                             <div class="flex justify-center mb-4">
                                 <i class="fa-brands fa-github text-4xl text-black"></i>
                             </div>
-                            <h3 class="text-2xl mb-4">Open Source</h3>
-                            <p>"We believe that the Web should be open that's why we share everything about nobt.io, except your data."</p>
+                            <h3 class="text-2xl mb-4">{t("Open Source")}</h3>
+                            <p>{t("We believe that the Web should be open that's why we share everything about nobt.io, except your data.")}</p>
                         </div>
                     </div>
                 </div>
@@ -83,9 +86,9 @@ pub async fn index() -> impl IntoResponse {
             <section id="team" class="py-20 bg-white text-center">
                 <div class="container mx-auto px-4 md:w-3/5">
                     <div class="mb-10">
-                        <h2 class="text-3xl">The team behind nobt.io.</h2>
-                        <p class="mt-4">Crafted with{NBSP}<span class="fa-solid fa-mug-saucer"></span>{NBSP}in Vienna and Sydney.</p>
-                        <p class="mt-4">"What started as a hackathon by three motivated developers soon turned out to be an actually helpful companion in our daily life. Nobt.io is our effort to share this idea with all of you. We hope you enjoy it as much as we do."</p>
+                        <h2 class="text-3xl">{t("The team behind nobt.io.")}</h2>
+                        <p class="mt-4">{t("Crafted with")}{NBSP}<span class="fa-solid fa-mug-saucer"></span>{NBSP}{t("in Vienna and Sydney.")}</p>
+                        <p class="mt-4">{t("What started as a hackathon by three motivated developers soon turned out to be an actually helpful companion in our daily life. Nobt.io is our effort to share this idea with all of you. We hope you enjoy it as much as we do.")}</p>
                     </div>
                     <div>
                         <TeamMember name="Thomas" github="thomaseizinger" linked_in="thomas-eizinger-b45a37144" homepage="https://eizinger.io" />
@@ -97,7 +100,7 @@ pub async fn index() -> impl IntoResponse {
 
             // TODO Add icons here
             <footer>
-                <a href="mailto:hello@nobt.io">Contact Us</a>
+                <a href="mailto:hello@nobt.io">{t("Contact Us")}</a>
                 <a href="https://twitter.com/nobtio">Twitter</a>
                 <a href="https://github.com/nobt-io">GitHub</a>
             </footer>
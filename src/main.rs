@@ -1,25 +1,53 @@
 use anyhow::{Context, Result};
-use axum::extract::Path;
-use axum::response::{Html, IntoResponse, Response};
+use axum::extract::{Path, State};
+use axum::http::header::SET_COOKIE;
+use axum::http::{HeaderValue, Request, StatusCode};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Redirect, Response};
 use axum::routing::get;
 use axum::routing::post;
-use axum::Router;
+use axum::{middleware, Router};
 use axum_extra::extract::Form;
 use rscx::{CollectFragment, CollectFragmentAsync, component, EscapeAttribute, html};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::net::SocketAddr;
+use std::sync::Arc;
+use time::{Date, OffsetDateTime};
 
 use responses::Css;
 use responses::Jpeg;
-use crate::responses::{Png};
+use crate::activity::{Activity, Actor, Event, FormerActor, ACTOR_COOKIE};
+use crate::error::AppError;
+use crate::format::Locale;
+use crate::i18n::{t, tf};
+use crate::responses::{Png, SeeOther};
 use crate::components::Head;
-
+use crate::money::{parse_decimal, Currency, ExchangeRate, Money};
+use crate::nobt::{
+    join_names, Bill, Category, Debtee, NewBill, NewNobt, NewPayment, Nobt, Participant, Split,
+    SplitMode,
+};
+use crate::sqlite::SqliteStorage;
+use crate::storage::Storage;
+use crate::validation::FieldErrors;
+
+mod activity;
+mod error;
+mod format;
 mod headers;
+mod i18n;
 mod responses;
 mod landing_page;
+mod balance;
 mod components;
+mod money;
+mod nobt;
+mod settlement;
+mod sqlite;
+mod storage;
+mod validation;
 
 const STYLES: &str = include_str!(concat!(env!("OUT_DIR"), "/style.css"));
 const NOT_FOUND_IMAGE: &[u8] = include_bytes!("../assets/stock-photo-stack-424916446.jpg");
@@ -35,28 +63,62 @@ async fn main() -> Result<()> {
         .unwrap_or("3000")
         .parse()
         .context("failed to parse port")?;
+    let database_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| "nobt.sqlite".to_owned());
 
-    let app = Router::new()
-        .route("/", get(landing_page::index))
-        .route("/style.css", get(|| async { Css(STYLES) }))
-        .route("/not_found.jpg", get(|| async { Jpeg(NOT_FOUND_IMAGE) }))
-        .route("/david.png", get(|| async { Png(DAVID_IMAGE) }))
-        .route("/thomas.png", get(|| async { Png(THOMAS_IMAGE) }))
-        .route("/matthias.png", get(|| async { Png(MATTHIAS_IMAGE) }))
-        .route("/landing_page_background.jpg", get(|| async { Jpeg(LANDING_PAGE_BACKGROUND_IMAGE) }))
+    let storage: Arc<dyn Storage> = Arc::new(SqliteStorage::open(database_path)?);
+
+    let nobts = Router::new()
         .route("/:nobt_id", get(nobt))
         .route("/:nobt_id/bill", get(new_bill))
         .route("/:nobt_id/bill", post(new_bill))
         .route("/:nobt_id/bill/new", post(add_new_bill))
+        .route("/:nobt_id/bill/validate/:field", post(validate_bill_field))
         .route("/:nobt_id/bill/debtee", get(choose_bill_debtee))
-        .route("/:nobt_id/bill/debtee", post(choose_bill_debtee))
+        .route("/:nobt_id/bill/debtee", post(add_bill_debtee))
         .route("/:nobt_id/bill/debtors", get(choose_bill_debtors))
-        .route("/:nobt_id/bill/debtors", post(choose_bill_debtors))
+        .route("/:nobt_id/bill/debtors", post(add_bill_debtor))
+        .route("/:nobt_id/bill/split", get(choose_bill_split))
+        .route("/:nobt_id/bill/split", post(choose_bill_split))
         .route("/:nobt_id/balances", get(balances))
         .route("/:nobt_id/balances/:name", get(individual_balance))
+        .route("/:nobt_id/settle", get(settle_up))
+        .route("/:nobt_id/participants", get(participants))
+        .route("/:nobt_id/participants", post(add_participant))
+        .route("/:nobt_id/participants/rename", post(rename_participant))
+        .route("/:nobt_id/participants/remove", post(remove_participant))
+        .route("/:nobt_id/participants/merge", get(preview_merge))
+        .route("/:nobt_id/participants/merge", post(merge_participants))
+        .route("/:nobt_id/payment", get(new_payment))
+        .route("/:nobt_id/payment", post(add_payment))
+        .route("/:nobt_id/activity", get(activity))
+        .route("/:nobt_id/activity/me", post(choose_actor))
         .route("/:nobt_id/:expense_id", get(expense))
+        .route("/:nobt_id/:expense_id/edit", get(edit_expense))
+        .route("/:nobt_id/:expense_id/edit", post(revise_expense))
         .route("/:nobt_id/:expense_id/delete", post(delete_expense))
-        .fallback(not_found);
+        .route("/:nobt_id/:expense_id/restore", post(restore_expense))
+        .route_layer(middleware::from_fn_with_state(
+            storage.clone(),
+            forget_former_actor,
+        ))
+        .route_layer(middleware::from_fn(check_nobt_id));
+
+    let app = Router::new()
+        .route("/", get(landing_page::index))
+        .route("/style.css", get(|| async { Css(STYLES) }))
+        .route("/not_found.jpg", get(|| async { Jpeg(NOT_FOUND_IMAGE) }))
+        .route("/david.png", get(|| async { Png(DAVID_IMAGE) }))
+        .route("/thomas.png", get(|| async { Png(THOMAS_IMAGE) }))
+        .route("/matthias.png", get(|| async { Png(MATTHIAS_IMAGE) }))
+        .route("/landing_page_background.jpg", get(|| async { Jpeg(LANDING_PAGE_BACKGROUND_IMAGE) }))
+        .route("/language/:code", get(i18n::switch_language))
+        .route("/create", get(create_nobt_form))
+        .route("/create", post(create_nobt))
+        .merge(nobts)
+        .fallback(|| async { StatusCode::NOT_FOUND })
+        .layer(middleware::map_response(error::render_error_pages))
+        .layer(middleware::from_fn(format::with_request_locale))
+        .with_state(storage);
 
     axum::Server::bind(&SocketAddr::from(([0, 0, 0, 0], port)))
         .serve(app.into_make_service())
@@ -65,42 +127,725 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Answers requests for nobts whose ID can't exist with a 404 before looking for them.
+///
+/// IDs typed in their human-friendly form are redirected to the canonical URL of the nobt.
+async fn check_nobt_id<B>(
+    Path(params): Path<HashMap<String, String>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let Some(nobt_id) = params.get("nobt_id") else {
+        return next.run(request).await;
+    };
+
+    match nobt::parse_id(nobt_id) {
+        Some(id) if id == *nobt_id => next.run(request).await,
+        Some(id) => {
+            let uri = request.uri();
+            let rest = uri.path()[1..].split_once('/').map_or("", |(_, rest)| rest);
+            let query = uri
+                .query()
+                .map(|query| format!("?{query}"))
+                .unwrap_or_default();
+            let location = if rest.is_empty() {
+                format!("/{id}{query}")
+            } else {
+                format!("/{id}/{rest}{query}")
+            };
+
+            Redirect::permanent(&location).into_response()
+        }
+        None => AppError::NobtNotFound.into_response(),
+    }
+}
+
+/// Forgets who is using the browser once they are no longer a participant of the nobt, e.g. after being renamed or removed.
+///
+/// The request is handled as if they hadn't said who they are, and the cookie naming them is removed.
+async fn forget_former_actor<B>(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(params): Path<HashMap<String, String>>,
+    Actor(actor): Actor,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Result<Response, AppError> {
+    let (Some(nobt_id), Some(actor)) = (params.get("nobt_id"), actor) else {
+        return Ok(next.run(request).await);
+    };
+    if storage
+        .get_nobt(nobt_id)?
+        .is_none_or(|nobt| nobt.has_participant(&actor))
+    {
+        return Ok(next.run(request).await);
+    }
+
+    request.extensions_mut().insert(FormerActor);
+    let mut response = next.run(request).await;
+
+    // Picking someone else on the activity page sets the cookie anew, which must not be undone.
+    let sets_actor = response.headers().get_all(SET_COOKIE).iter().any(|cookie| {
+        cookie
+            .as_bytes()
+            .starts_with(format!("{ACTOR_COOKIE}=").as_bytes())
+    });
+    if !sets_actor {
+        if let Ok(cookie) = HeaderValue::try_from(activity::forget_actor_cookie(nobt_id)) {
+            response.headers_mut().append(SET_COOKIE, cookie);
+        }
+    }
+
+    Ok(response)
+}
+
 #[derive(serde::Deserialize, serde::Serialize)]
 struct NewBillParameters {
     name: Option<String>,
-    total: Option<f64>,
+    total: Option<String>,
+    /// The currency the bill was paid in, if it isn't the one of the nobt.
+    currency: Option<String>,
+    /// Converts amounts of `currency` into the currency of the nobt.
+    exchange_rate: Option<String>,
+    /// The currency `exchange_rate` was entered for, so that it is not applied to another one after switching.
+    rate_currency: Option<String>,
     debtee: Option<String>,
+    #[serde(default)]
+    debtee_names: Vec<String>,
+    #[serde(default)]
+    debtee_amounts: Vec<String>,
     debtors: Option<HashSet<String>>,
+    split_mode: Option<String>,
+    #[serde(default)]
+    split_names: Vec<String>,
+    #[serde(default)]
+    split_values: Vec<String>,
+    /// The ID of the bill that is being edited, if any.
+    revision_of: Option<u64>,
+    /// A participant to add to the nobt, entered in the "Someone else?" forms of the wizard.
+    new_participant: Option<String>,
+    /// The day the bill was paid on, e.g. `2022-08-28`.
+    date: Option<String>,
+    category: Option<String>,
+    /// The name of a new category, which is used instead of `category` if entered.
+    custom_category: Option<String>,
 }
 
-async fn nobt(Path(nobt_id): Path<String>) -> impl IntoResponse {
-    let title = "Swedish Shenanigans";
-    let total = 1521.00;
-    let currency = "EUR";
-    let num_participants = 4;
-    let expenses = vec![
-        ExpenseItem {
-            description: "Thomas paid 'Flughafen Essen'".to_owned(),
-            amount: 39.00,
-            url: format!("/{nobt_id}/14"),
-            deleted: false,
-        },
-        ExpenseItem {
-            description: "Thomas paid 'Benni Gutschein'".to_owned(),
-            amount: 150.00,
-            url: format!("/{nobt_id}/13"),
-            deleted: true,
-        },
-        ExpenseItem {
-            description: "Prada paid 'Taxi zum Club'".to_owned(),
-            amount: 33.00,
-            url: format!("/{nobt_id}/12"),
-            deleted: false,
-        },
-    ];
+impl NewBillParameters {
+    /// Pre-fills the wizard with a stored bill, to edit it.
+    fn from_bill(bill: &Bill) -> Self {
+        let (debtee, debtee_names, debtee_amounts) = match bill.debtees.as_slice() {
+            [debtee] => (Some(debtee.name.clone()), Vec::new(), Vec::new()),
+            debtees => (
+                None,
+                debtees.iter().map(|d| d.name.clone()).collect(),
+                debtees
+                    .iter()
+                    .map(|d| d.amount.to_decimal_string())
+                    .collect(),
+            ),
+        };
+        let split_values = match &bill.split {
+            Split::Equal => Vec::new(),
+            Split::Shares(shares) => shares.iter().map(|s| s.to_string()).collect(),
+            Split::Percentages(percentages) => percentages
+                .iter()
+                .map(|p| format!("{}.{:02}", p / 100, p % 100))
+                .collect(),
+            Split::Exact(amounts) => amounts.iter().map(|a| a.to_decimal_string()).collect(),
+        };
+
+        Self {
+            name: Some(bill.name.clone()),
+            total: Some(bill.total.to_decimal_string()),
+            currency: bill.exchange_rate.map(|rate| rate.from().code().to_owned()),
+            exchange_rate: bill.exchange_rate.map(|rate| rate.to_decimal_string()),
+            rate_currency: bill.exchange_rate.map(|rate| rate.from().code().to_owned()),
+            debtee,
+            debtee_names,
+            debtee_amounts,
+            debtors: Some(bill.debtors.iter().cloned().collect()),
+            split_mode: Some(bill.split.mode().as_str().to_owned()),
+            split_names: bill.debtors.clone(),
+            split_values,
+            revision_of: Some(bill.id),
+            new_participant: None,
+            date: Some(bill.date.to_string()),
+            category: Some(bill.category.as_str().to_owned()),
+            custom_category: None,
+        }
+    }
+
+    /// Everyone who paid a part of the bill, if several people paid it.
+    fn partial_debtees(&self) -> Vec<&str> {
+        self.debtee_names
+            .iter()
+            .zip(&self.debtee_amounts)
+            .filter(|(_, amount)| !amount.trim().is_empty())
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// The amount entered for the given person on the debtee step, if any.
+    fn debtee_amount(&self, name: &str) -> Option<&str> {
+        entered_value(&self.debtee_names, &self.debtee_amounts, name)
+    }
+
+    /// The currency entered amounts are in, falling back to the one of the nobt.
+    fn currency(&self, nobt: &Nobt) -> Currency {
+        self.parse_currency(nobt).unwrap_or(nobt.currency)
+    }
+
+    /// The exchange rate entered for the currency, ignoring a rate that was entered for another one.
+    fn exchange_rate_of(&self, currency: Currency) -> Option<&str> {
+        if self.rate_currency.as_deref() != Some(currency.code()) {
+            return None;
+        }
+
+        self.exchange_rate
+            .as_deref()
+            .map(str::trim)
+            .filter(|rate| !rate.is_empty())
+    }
+
+    fn split_mode(&self) -> SplitMode {
+        self.split_mode
+            .as_deref()
+            .and_then(|mode| mode.parse().ok())
+            .unwrap_or(SplitMode::Equal)
+    }
+
+    /// The value entered for the given debtor on the split step, if any.
+    fn split_value(&self, name: &str) -> Option<&str> {
+        entered_value(&self.split_names, &self.split_values, name)
+    }
+
+    /// Checks everything entered in the wizard, collecting the errors of all fields.
+    fn validate(&self, nobt: &Nobt) -> Result<NewBill, FieldErrors> {
+        let mut errors = FieldErrors::default();
+
+        let name = errors.check("name", self.parse_name());
+        let date = errors.check("date", self.parse_date());
+        let category = errors.check("category", self.parse_category());
+        let currency = errors.check("total", self.parse_currency(nobt));
+        let total = currency.and_then(|currency| errors.check("total", self.parse_total(currency)));
+        let exchange_rate = currency.and_then(|currency| {
+            errors.check("exchange_rate", self.parse_exchange_rate(nobt, currency))
+        });
+        if let (Some(total), Some(Some(rate))) = (total, exchange_rate) {
+            if rate.checked_convert(total).is_none() {
+                errors.add("total", AMOUNT_TOO_LARGE);
+            }
+        }
+        let debtees =
+            total.and_then(|total| errors.check("debtee", self.parse_debtees(nobt, total)));
+        let debtors = errors.check("debtors", self.parse_debtors(nobt));
+        let split = match (&debtors, total) {
+            (Some(debtors), Some(total)) => errors.check("split", self.parse_split(debtors, total)),
+            _ => None,
+        };
+
+        match (
+            name,
+            date,
+            category,
+            total,
+            debtees,
+            debtors,
+            split,
+            exchange_rate,
+        ) {
+            (
+                Some(name),
+                Some(date),
+                Some(category),
+                Some(total),
+                Some(debtees),
+                Some(debtors),
+                Some(split),
+                Some(exchange_rate),
+            ) if errors.is_empty() => Ok(NewBill {
+                name,
+                total,
+                debtees,
+                debtors,
+                split,
+                exchange_rate,
+                date,
+                category,
+            }),
+            _ => Err(errors),
+        }
+    }
+
+    fn parse_name(&self) -> Result<String, &'static str> {
+        match self.name.as_deref().map(str::trim) {
+            None | Some("") => Err("The bill needs a name."),
+            Some(name) => Ok(name.to_owned()),
+        }
+    }
+
+    /// The day the bill was paid on, today unless another day was entered.
+    fn parse_date(&self) -> Result<Date, &'static str> {
+        match self.date.as_deref() {
+            None => Ok(today()),
+            Some(date) => format::parse_iso_date(date).ok_or("Enter the day the bill was paid on."),
+        }
+    }
+
+    fn parse_category(&self) -> Result<Category, &'static str> {
+        if let Some(name) = self
+            .custom_category
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+        {
+            let name = validation::category_name(name)?;
+
+            // Naming a built-in category, in any language, picks it rather than making up a look-alike.
+            let built_in = Category::BUILT_IN.iter().find(|category| {
+                [category.as_str(), category.label(), t(category.label())]
+                    .iter()
+                    .any(|built_in| built_in.to_lowercase() == name.to_lowercase())
+            });
+            return Ok(built_in
+                .cloned()
+                .unwrap_or_else(|| Category::Custom(name.to_owned())));
+        }
+
+        match self.category.as_deref().map(str::trim) {
+            None | Some("") => Ok(Category::Other),
+            Some(category) => Ok(Category::from_stored(category)),
+        }
+    }
+
+    fn parse_total(&self, currency: Currency) -> Result<Money, &'static str> {
+        let total = self.total.as_deref().unwrap_or_default();
+
+        match Money::parse(total, currency) {
+            Some(total) if total.is_positive() => Ok(total),
+            None if Money::is_too_large(total, currency) => Err(AMOUNT_TOO_LARGE),
+            _ => Err("The total of a bill must be a positive amount."),
+        }
+    }
+
+    fn parse_currency(&self, nobt: &Nobt) -> Result<Currency, &'static str> {
+        match self.currency.as_deref().map(str::trim) {
+            None | Some("") => Ok(nobt.currency),
+            Some(code) => code
+                .parse()
+                .map_err(|_| "Choose one of the listed currencies."),
+        }
+    }
+
+    /// Bills paid in another currency than the one of the nobt need a rate to convert them.
+    fn parse_exchange_rate(
+        &self,
+        nobt: &Nobt,
+        currency: Currency,
+    ) -> Result<Option<ExchangeRate>, &'static str> {
+        if currency == nobt.currency {
+            return Ok(None);
+        }
+
+        match self.exchange_rate_of(currency) {
+            None => Err("Enter the exchange rate of the currency the bill was paid in."),
+            Some(rate) => ExchangeRate::parse(rate, currency, nobt.currency)
+                .map(Some)
+                .ok_or(
+                    "The exchange rate must be a positive number with at most six decimal places.",
+                ),
+        }
+    }
+
+    /// Either a single debtee paid the whole bill, or several debtees paid the amounts entered for them.
+    fn parse_debtees(&self, nobt: &Nobt, total: Money) -> Result<Vec<Debtee>, &'static str> {
+        let mut debtees = Vec::<Debtee>::new();
+        for (name, amount) in self.debtee_names.iter().zip(&self.debtee_amounts) {
+            let amount = amount.trim();
+            if name.trim().is_empty() || amount.is_empty() {
+                continue;
+            }
+            let name = participant(nobt, name)?;
+
+            let amount = match Money::parse(amount, total.currency()) {
+                Some(amount) if amount.is_zero() => continue,
+                Some(amount) if amount.is_positive() => amount,
+                None if Money::is_too_large(amount, total.currency()) => {
+                    return Err(AMOUNT_TOO_LARGE)
+                }
+                _ => return Err("Paid amounts must be positive."),
+            };
+            match debtees.iter_mut().find(|d| d.name == name) {
+                Some(debtee) => debtee.amount += amount,
+                None => debtees.push(Debtee {
+                    name: name.to_owned(),
+                    amount,
+                }),
+            }
+        }
+
+        if debtees.is_empty() {
+            let debtee = match self.debtee.as_deref().map(str::trim) {
+                None | Some("") => return Err("Someone needs to have paid the bill."),
+                Some(debtee) => participant(nobt, debtee)?,
+            };
+
+            return Ok(vec![Debtee {
+                name: debtee.to_owned(),
+                amount: total,
+            }]);
+        }
+
+        if debtees
+            .iter()
+            .fold(Money::zero(total.currency()), |sum, d| sum + d.amount)
+            != total
+        {
+            return Err("The paid amounts must add up to the total of the bill.");
+        }
+
+        Ok(debtees)
+    }
+
+    /// Parses the values entered for each debtor and checks that they add up.
+    fn parse_split(&self, debtors: &[String], total: Money) -> Result<Split, &'static str> {
+        let mode = match self.split_mode.as_deref().map(str::trim) {
+            None | Some("") => SplitMode::Equal,
+            Some(mode) => mode.parse().map_err(|_| "Choose how the bill is split.")?,
+        };
+        if mode == SplitMode::Equal {
+            return Ok(Split::Equal);
+        }
+
+        let values = debtors
+            .iter()
+            .map(|debtor| {
+                entered_value(&self.split_names, &self.split_values, debtor)
+                    .filter(|value| !value.is_empty())
+                    .ok_or("Enter a value for everyone involved in the bill.")
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match mode {
+            SplitMode::Equal => Ok(Split::Equal),
+            SplitMode::Shares => values
+                .iter()
+                .map(|value| {
+                    value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|shares| *shares > 0)
+                        .map(u64::from)
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(Split::Shares)
+                .ok_or("Shares must be whole numbers greater than zero."),
+            SplitMode::Percentages => {
+                // In hundredths of a percent, so 33.33% become 3333.
+                let percentages = values
+                    .iter()
+                    .map(|value| {
+                        parse_decimal(value, 2)
+                            .filter(|p| (0..=10_000).contains(p))
+                            .map(|p| p as u64)
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or(
+                        "Percentages must be between 0 and 100 with at most two decimal places.",
+                    )?;
+                if percentages.iter().sum::<u64>() != 10_000 {
+                    return Err("The percentages must add up to 100%.");
+                }
+
+                Ok(Split::Percentages(percentages))
+            }
+            SplitMode::Exact => {
+                let amounts = values
+                    .iter()
+                    .map(|value| match Money::parse(value, total.currency()) {
+                        Some(amount) if amount.is_negative() => {
+                            Err("Amounts must not be negative.")
+                        }
+                        Some(amount) => Ok(amount),
+                        None if Money::is_too_large(value, total.currency()) => {
+                            Err(AMOUNT_TOO_LARGE)
+                        }
+                        None => Err("Enter a valid amount for everyone involved in the bill."),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if amounts
+                    .iter()
+                    .fold(Money::zero(total.currency()), |sum, amount| sum + *amount)
+                    != total
+                {
+                    return Err("The amounts must add up to the total of the bill.");
+                }
+
+                Ok(Split::Exact(amounts))
+            }
+        }
+    }
+
+    /// The debtors in the order they are shown in the wizard.
+    fn parse_debtors(&self, nobt: &Nobt) -> Result<Vec<String>, &'static str> {
+        let mut debtors = self
+            .debtors
+            .iter()
+            .flatten()
+            .filter(|debtor| !debtor.trim().is_empty())
+            .map(|debtor| participant(nobt, debtor).map(|d| d.to_owned()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if debtors.is_empty() {
+            return Err("At least one person needs to be involved in the bill.");
+        }
+
+        debtors.sort_by_key(|debtor| nobt.participant_names().position(|name| name == debtor));
+        debtors.dedup();
+
+        Ok(debtors)
+    }
+}
+
+/// The error for amounts above [`Money::MAX_MINOR_UNITS`], which we refuse so that sums of amounts can't overflow.
+const AMOUNT_TOO_LARGE: &str = "This amount is too large.";
+
+/// The current day, which new bills are paid on unless another day is entered.
+fn today() -> Date {
+    OffsetDateTime::now_utc().date()
+}
+
+/// Checks that a name entered in the bill wizard belongs to a participant of the nobt.
+fn participant<'n>(nobt: &'n Nobt, name: &str) -> Result<&'n str, &'static str> {
+    let name = validation::person_name(name)?;
+
+    nobt.participant_names()
+        .find(|participant| *participant == name)
+        .ok_or("Only participants of this nobt can be part of a bill.")
+}
+
+/// Looks up the value that was entered for a person in a form with one input per person.
+///
+/// Such forms submit the names and the values as two lists in the same order.
+fn entered_value<'v>(names: &'v [String], values: &'v [String], name: &str) -> Option<&'v str> {
+    names
+        .iter()
+        .zip(values)
+        .find(|(n, _)| n.trim() == name)
+        .map(|(_, value)| value.trim())
+}
+
+/// Pre-fills the exchange rate of a bill in another currency from the rate table, unless a rate was entered.
+fn prefill_exchange_rate(
+    storage: &dyn Storage,
+    nobt: &Nobt,
+    params: &mut NewBillParameters,
+) -> Result<()> {
+    let currency = params.currency(nobt);
+    if currency == nobt.currency || params.exchange_rate_of(currency).is_some() {
+        return Ok(());
+    }
+
+    params.exchange_rate = storage
+        .exchange_rate(currency, nobt.currency)?
+        .map(|rate| rate.to_decimal_string());
+    params.rate_currency = Some(currency.code().to_owned());
+
+    Ok(())
+}
+
+async fn create_nobt_form() -> impl IntoResponse {
+    Html(create_nobt_page(
+        &CreateNobtForm::default(),
+        &FieldErrors::default(),
+    ))
+}
+
+async fn create_nobt(
+    State(storage): State<Arc<dyn Storage>>,
+    Form(form): Form<CreateNobtForm>,
+) -> Result<Response, AppError> {
+    let new_nobt = match form.validate() {
+        Ok(new_nobt) => new_nobt,
+        Err(errors) => {
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Html(create_nobt_page(&form, &errors)),
+            )
+                .into_response())
+        }
+    };
+
+    let nobt_id = nobt::new_id();
+    storage.create_nobt(&nobt_id, new_nobt)?;
+
+    Ok(SeeOther(format!("/{nobt_id}")).into_response())
+}
+
+/// The form for creating a nobt, filled in with what was entered so far.
+fn create_nobt_page(form: &CreateNobtForm, errors: &FieldErrors) -> String {
+    html! {
+        <App title={t("Create a nobt")}>
+            <Header>
+                <BackLink href="/"/>
+                <HeaderTitle title={t("Create a nobt")} />
+            </Header>
+            <form class="bg-turquoise p-4 flex flex-col gap-4" method="post" action="/create">
+                <section class="flex flex-col bg-white p-2">
+                    <h2 class="text-black font-bold text-sm">{t("What are you planning?")}</h2>
+                    <input required="true" class="outline-none peer border-b py-2" name="title" value=&form.title placeholder={t("Road Trip, Summer Holidays, Flat Share, ...")} />
+                    <span class="text-xs text-[grey]">{t("Give your nobt a name so everyone recognizes it.")}</span>
+                    <FieldError message=errors.get("title") />
+                </section>
+                <section class="flex flex-col bg-white p-2">
+                    <h2 class="text-black font-bold text-sm">{t("Which currency do you use?")}</h2>
+                    <select class="outline-none peer border-b py-2 bg-white" name="currency">
+                        {Currency::ALL
+                            .iter()
+                            .map(|currency| {
+                                let label = format!("{} ({})", currency.code(), currency.symbol());
+                                if form.currency == currency.code() {
+                                    html! { <option value={currency.code()} selected="selected">{label}</option> }
+                                } else {
+                                    html! { <option value={currency.code()}>{label}</option> }
+                                }
+                            })
+                            .collect_fragment()}
+                    </select>
+                    <span class="text-xs text-[grey]">{t("All bills of this nobt are entered in this currency.")}</span>
+                    <FieldError message=errors.get("currency") />
+                </section>
+                <section class="flex flex-col bg-white p-2">
+                    <h2 class="text-black font-bold text-sm">{t("Who is in?")}</h2>
+                    <input class="outline-none peer border-b py-2" name="participants" value=&form.participants placeholder="Bart, Milhouse, Nelson, ..." />
+                    <span class="text-xs text-[grey]">{t("Separate names with a comma. You can always add more people later.")}</span>
+                    <FieldError message=errors.get("participants") />
+                </section>
+                <div>
+                    <button class="flex items-center justify-center gap-2 text-white uppercase rounded shadow px-4 py-2 bg-darkGreen" type="submit">
+                        <Icon name="check_circle" />
+                        {t("Create nobt")}
+                    </button>
+                </div>
+            </form>
+        </App>
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+struct CreateNobtForm {
+    title: String,
+    currency: String,
+    #[serde(default)]
+    participants: String,
+}
+
+impl CreateNobtForm {
+    fn validate(&self) -> Result<NewNobt, FieldErrors> {
+        let mut errors = FieldErrors::default();
+
+        let title = errors.check("title", validation::nobt_title(&self.title));
+        let currency = errors.check("currency", self.parse_currency());
+        let participants = errors.check("participants", self.parse_participants());
+
+        match (title, currency, participants) {
+            (Some(title), Some(currency), Some(participants)) => Ok(NewNobt {
+                title: title.to_owned(),
+                currency,
+                participants,
+            }),
+            _ => Err(errors),
+        }
+    }
+
+    fn parse_currency(&self) -> Result<Currency, &'static str> {
+        self.currency
+            .parse()
+            .map_err(|_| "The currency is not supported.")
+    }
+
+    fn parse_participants(&self) -> Result<Vec<String>, &'static str> {
+        let mut participants = Vec::<String>::new();
+        for name in self
+            .participants
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+        {
+            let name = validation::person_name(name)?;
+            if !participants.iter().any(|p| p == name) {
+                participants.push(name.to_owned());
+            }
+        }
+
+        Ok(participants)
+    }
+}
+
+async fn nobt(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let title = nobt.title.as_str();
+    let total = nobt.total();
+    let num_participants = nobt.participants.len();
+    let category_totals = nobt.category_totals();
+    let bills = nobt.current_bills().rev().map(|bill| {
+        (
+            bill.created_at,
+            ExpenseItem {
+                description: tf(
+                    "{debtees} paid '{bill}'",
+                    &[("debtees", &bill.debtee_names()), ("bill", &bill.name)],
+                ),
+                amount: bill.total,
+                icon: bill.category.icon(),
+                url: Some(format!("/{nobt_id}/{}", bill.id)),
+                deleted: bill.deleted,
+                date: bill.date,
+            },
+        )
+    });
+    let payments = nobt.payments.iter().rev().map(|payment| {
+        let description = match &payment.note {
+            Some(note) => tf(
+                "{from} paid {to} '{note}'",
+                &[("from", &payment.from), ("to", &payment.to), ("note", note)],
+            ),
+            None => tf(
+                "{from} paid {to}",
+                &[("from", &payment.from), ("to", &payment.to)],
+            ),
+        };
+
+        (
+            payment.created_at,
+            ExpenseItem {
+                description,
+                amount: payment.amount,
+                icon: "payments",
+                url: None,
+                deleted: false,
+                date: payment.created_at.date(),
+            },
+        )
+    });
+    let mut expenses = bills.chain(payments).collect::<Vec<_>>();
+    expenses.sort_by(|(a, x), (b, y)| (y.date, b).cmp(&(x.date, a)));
+    let mut days = Vec::<(Date, Vec<ExpenseItem>)>::new();
+    for (_, expense) in expenses {
+        match days.last_mut() {
+            Some((day, expenses)) if *day == expense.date => expenses.push(expense),
+            _ => days.push((expense.date, vec![expense])),
+        }
+    }
     let balances_url = format!("/{nobt_id}/balances");
+    let settle_url = format!("/{nobt_id}/settle");
+    let participants_url = format!("/{nobt_id}/participants");
+    let activity_url = format!("/{nobt_id}/activity");
 
-    Html(html! {
+    Ok(Html(html! {
         <App title=title>
             <Header>
                 <h1 class="text-xl">"nobt.io"</h1>
@@ -113,121 +858,193 @@ async fn nobt(Path(nobt_id): Path<String>) -> impl IntoResponse {
                     <li class="inline-block">
                         <div class="flex items-center gap-2 text-sm">
                             <Icon name="credit_card" />
-                            <Amount currency=currency value=total classes=""/>
+                            <Amount value=total classes=""/>
                         </div>
                     </li>
                     <li class="inline-block">
-                        <div class="flex items-center gap-2 text-sm">
+                        <a href=participants_url class="flex items-center gap-2 text-sm" preload="mousedown">
                             <Icon name="group" />
                             {num_participants}
+                        </a>
+                    </li>
+                    <li class="inline-block">
+                        <div class="flex items-center gap-2 text-sm" title={t("Share this code with your friends so they can open the nobt.")}>
+                            <Icon name="key" />
+                            <span class="font-mono">{nobt::display_id(&nobt_id)}</span>
                         </div>
                     </li>
+                    <li class="inline-block">
+                        <a href=activity_url class="flex items-center gap-2 text-sm" preload="mousedown" title={t("Who changed what in this nobt")}>
+                            <Icon name="history" />
+                            {t("Activity")}
+                        </a>
+                    </li>
                 </ul>
-                <div class="text-center">
-                    <a href=balances_url class="uppercase inline-block bg-darkGreen px-3 py-2" preload="mousedown">"Show balances"</a>
-                </div>
-            </div>
-            <div class="bg-white p-4">
-                <List>
-                    {expenses
+                <ul class="flex flex-wrap items-center justify-center gap-x-4 gap-y-2" aria-label={t("Spent per category")}>
+                    {category_totals
                         .iter()
-                        .map(|expense| async {
-                            let classes = if expense.deleted {
-                                "grow flex flex-col line-through opacity-30"
-                            } else {
-                                "grow flex flex-col"
-                            };
-
+                        .map(|(category, total)| async move {
                             html! {
-                                <LinkListItem href=&expense.url>
-                                    <ListItemIcon name="receipt"/>
-                                    <span class={classes}>
-                                        <span>{expense.description.as_str()}</span>
-                                        <Amount currency=currency value=expense.amount classes="text-darkGrey"/>
-                                    </span>
-                                </LinkListItem>
+                                <li class="flex items-center gap-1 text-sm" title={category_label(category)}>
+                                    <Icon name=category.icon() />
+                                    <Amount value=*total classes=""/>
+                                </li>
                             }
                         })
                         .collect_fragment_async().await}
-                </List>
+                </ul>
+                <div class="flex items-center justify-center gap-4">
+                    <a href=balances_url class="uppercase inline-block bg-darkGreen px-3 py-2" preload="mousedown">{t("Show balances")}</a>
+                    <a href=settle_url class="uppercase inline-block bg-darkGreen px-3 py-2" preload="mousedown">{t("Settle up")}</a>
+                </div>
+            </div>
+            <div class="bg-white p-4 flex flex-col gap-4">
+                {days
+                    .iter()
+                    .map(|(day, expenses)| async move {
+                        html! {
+                            <section>
+                                <h3 class="text-darkGrey text-xs uppercase px-2">{day_label(*day)}</h3>
+                                <List>
+                                    {expenses
+                                        .iter()
+                                        .map(|expense| async {
+                                            let classes = if expense.deleted {
+                                                "grow flex flex-col line-through opacity-30"
+                                            } else {
+                                                "grow flex flex-col"
+                                            };
+
+                                            let content = html! {
+                                                <ListItemIcon name=expense.icon/>
+                                                <span class={classes}>
+                                                    <span>{expense.description.as_str()}</span>
+                                                    <Amount value=expense.amount classes="text-darkGrey"/>
+                                                </span>
+                                            };
+
+                                            match &expense.url {
+                                                Some(url) => html! {
+                                                    <LinkListItem href=url>{content}</LinkListItem>
+                                                },
+                                                None => html! {
+                                                    <ListItem>{content}</ListItem>
+                                                },
+                                            }
+                                        })
+                                        .collect_fragment_async().await}
+                                </List>
+                            </section>
+                        }
+                    })
+                    .collect_fragment_async().await}
             </div>
             <FAB nobt_id=&nobt_id/>
         </App>
     })
+    .into_response())
+}
+
+/// The header of the expenses of a day in the list of a nobt.
+fn day_label(day: Date) -> String {
+    let today = today();
+
+    if day == today {
+        t("Today").to_owned()
+    } else if today.previous_day() == Some(day) {
+        t("Yesterday").to_owned()
+    } else {
+        format::date(day, Locale::current())
+    }
 }
 
 async fn new_bill(
+    State(storage): State<Arc<dyn Storage>>,
     Path(nobt_id): Path<String>,
-    Form(params): Form<NewBillParameters>,
-) -> impl IntoResponse {
-    let title = "Swedish Shenanigans";
-    let _currency = "EUR".to_owned();
-    let nobt_url = format!("/{nobt_id}");
-    let mut names = HashSet::from([
-        "Thomas".to_owned(),
-        "Simon".to_owned(),
-        "Prada".to_owned(),
-        "Benji".to_owned(),
-    ]);
+    Form(mut params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    prefill_exchange_rate(&*storage, &nobt, &mut params)?;
 
-    // TODO: Merge into component?
-    if let Some(new_debtee) = &params.debtee {
-        names.insert(new_debtee.to_owned());
-    }
+    Ok(Html(bill_wizard(&nobt, &params, &FieldErrors::default()).await).into_response())
+}
 
+/// The first page of the bill wizard, showing everything entered so far.
+///
+/// Picking the debtees, debtors or the split happens on separate pages which post back here.
+async fn bill_wizard(nobt: &Nobt, params: &NewBillParameters, errors: &FieldErrors) -> String {
+    let nobt_id = nobt.id.as_str();
+    let title = nobt.title.as_str();
+    let nobt_url = format!("/{nobt_id}");
+    let names = nobt
+        .participant_names()
+        .map(|name| name.to_owned())
+        .collect::<HashSet<_>>();
     let debtors = params.debtors.as_ref().unwrap_or_else(|| &names);
+    let partial_debtees = params.partial_debtees();
+    let (back_link, header_title, submit_url, submit_text) = match params.revision_of {
+        Some(bill_id) => (
+            format!("/{nobt_id}/{bill_id}"),
+            t("Edit bill"),
+            format!("/{nobt_id}/{bill_id}/edit"),
+            t("Save changes"),
+        ),
+        None => (
+            nobt_url,
+            t("Add a bill"),
+            format!("/{nobt_id}/bill/new"),
+            t("Add bill"),
+        ),
+    };
 
-    Html(html! {
+    html! {
         <App title=title>
             <Header>
-                <BackLink href=&nobt_url/>
-                <HeaderTitle title="Add a bill" />
+                <BackLink href=&back_link/>
+                <HeaderTitle title=header_title />
             </Header>
             <form class="bg-turquoise p-4 flex flex-col gap-4">
-                <section class="flex flex-col bg-white p-2">
-                    <h2 class="text-black font-bold text-sm">"What did you buy?"</h2>
-                    <input required="true" class="outline-none peer border-b py-2" name="name" value={params.name.unwrap_or_default()} placeholder="Trip Snacks, Train Tickets, Beer, ..." />
-                    <span class="text-xs text-[grey]">"Enter a descriptive name for what was paid for."</span>
-                </section>
-                <section class="flex flex-col bg-white p-2">
-                    <h2 class="text-black font-bold text-sm">"How much did it cost?"</h2>
-                    <div class="flex items-center">
-                        <span class="w-10 h-10 text-[grey] flex items-center justify-center text-xl">"€"</span>
-                        <input required="true" class="outline-none peer border-b py-2 appearance-none w-full" name="total" value={params.total.map(|t| t.to_string()).unwrap_or_default()} step="0.01" min="0" type="number" placeholder="0.00" /> // TODO: Don't set 0 by default
-                    </div>
-                    <span class="text-xs text-[grey]">"Enter the total of this bill."</span>
-                </section>
+                <BillNameField nobt_id=nobt_id params=params error=errors.get("name") />
+                <BillDateField nobt_id=nobt_id params=params error=errors.get("date") />
+                <BillCategoryField nobt=nobt params=params error=errors.get("category") />
+                <BillTotalField nobt=nobt params=params error=errors.get("total").or(errors.get("exchange_rate")) />
                 <section class="flex flex-col bg-white p-2 gap-2">
-                        <h2 class="text-black font-bold text-sm">"Who paid?"</h2>
+                        <h2 class="text-black font-bold text-sm">{t("Who paid?")}</h2>
                     <button type="submit" formnovalidate="true" formmethod="post" formaction={format!("/{nobt_id}/bill/debtee")} class="flex items-center hover:bg-hover cursor-pointer">
                         <span class="w-10 h-10 flex items-center justify-center text-xl text-[grey] material-symbols-outlined">
                             "person"
-                            {match params.debtee.as_deref() {
-                                Some(debtee) => html! {
-                                    <input class="appearance-none" required="true" type="radio" name="debtee" checked="checked" value=debtee />
-                                },
-                                None => html! {
-                                    <input class="appearance-none" required="true" type="radio" name="debtee" />
+                            {if params.debtee.is_some() || !partial_debtees.is_empty() {
+                                html! {
+                                    <input class="appearance-none" required="true" type="radio" checked="checked" />
+                                }
+                            } else {
+                                html! {
+                                    <input class="appearance-none" required="true" type="radio" />
                                 }
                             }}
                         </span>
-                        <span class={match &params.debtee {
-                            Some(_) => "text-black text-left flex-grow",
-                            None => "text-[grey] text-left flex-grow",
+                        <HiddenBillInputs params=params fields=&["debtee"] />
+                        <span class={match (&params.debtee, partial_debtees.is_empty()) {
+                            (None, true) => "text-[grey] text-left flex-grow",
+                            _ => "text-black text-left flex-grow",
                         }}>
                             {match &params.debtee {
-                                Some(debtee) => format!("{debtee} paid the bill."),
-                                None => "Select a Debtee".to_owned(),
+                                Some(debtee) => tf("{name} paid the bill.", &[("name", debtee)]),
+                                None if !partial_debtees.is_empty() => tf("{name} paid the bill.", &[("name", &join_names(&partial_debtees))]),
+                                None => t("Select a Debtee").to_owned(),
                             }}
                         </span>
                         <span class="w-10 h-10 flex items-center justify-center text-xl text-[grey] material-symbols-outlined">
                             "edit"
                         </span>
                     </button>
-                    <span class="text-xs text-[grey]">"Select the person who paid this bill."</span>
+                    <span class="text-xs text-[grey]">{t("Select the person or persons who paid this bill.")}</span>
+                    <FieldError message=errors.get("debtee") />
                 </section>
                 <section class="flex flex-col bg-white p-2 gap-2">
-                    <h2 class="text-black font-bold text-sm">"Who is involved?"</h2>
+                    <h2 class="text-black font-bold text-sm">{t("Who is involved?")}</h2>
                     <button type="submit" formnovalidate="true" formmethod="post" formaction={format!("/{nobt_id}/bill/debtors")} class="flex items-center hover:bg-hover cursor-pointer">
                         <span class="w-10 h-10 flex items-center justify-center text-xl text-[grey] material-symbols-outlined">
                             "group"
@@ -243,117 +1060,408 @@ async fn new_bill(
                             _ => "text-black text-left flex-grow",
                         }}>
                             {match debtors.len() {
-                                0 => "Nobody is involved".to_owned(),
-                                1 => "1 person is involved".to_owned(),
-                                num => format!("{num} persons are involved."),
+                                0 => t("Nobody is involved").to_owned(),
+                                1 => t("1 person is involved").to_owned(),
+                                num => tf("{num} persons are involved.", &[("num", &num)]),
                             }}
                         </span>
                         <span class="w-10 h-10 flex items-center justify-center text-xl text-[grey] material-symbols-outlined">
                             "edit"
                         </span>
                     </button>
-                    <span class="text-xs text-[grey]">"Select who is involved in this bill."</span>
+                    <span class="text-xs text-[grey]">{t("Select who is involved in this bill.")}</span>
+                    <FieldError message=errors.get("debtors") />
+                </section>
+                <section class="flex flex-col bg-white p-2 gap-2">
+                    <h2 class="text-black font-bold text-sm">{t("How is it split?")}</h2>
+                    <button type="submit" formnovalidate="true" formmethod="post" formaction={format!("/{nobt_id}/bill/split")} class="flex items-center hover:bg-hover cursor-pointer">
+                        <span class="w-10 h-10 flex items-center justify-center text-xl text-[grey] material-symbols-outlined">
+                            "balance"
+                        </span>
+                        <HiddenBillInputs params=params fields=&["split"] />
+                        <span class="text-black text-left flex-grow">
+                            {split_summary(params.split_mode())}
+                        </span>
+                        <span class="w-10 h-10 flex items-center justify-center text-xl text-[grey] material-symbols-outlined">
+                            "edit"
+                        </span>
+                    </button>
+                    <span class="text-xs text-[grey]">{t("Select how the total is divided between the people involved.")}</span>
+                    <FieldError message=errors.get("split") />
                 </section>
                 <div>
-                    <button class="flex items-center justify-center gap-2 text-white uppercase rounded shadow px-4 py-2 bg-darkGreen" type="submit" formmethod="post" formaction={format!("/{nobt_id}/bill/new")}>
+                    <HiddenBillInputs params=params fields=&["revision_of"] />
+                    <button class="flex items-center justify-center gap-2 text-white uppercase rounded shadow px-4 py-2 bg-darkGreen" type="submit" formmethod="post" formaction=submit_url>
                         <Icon name="check_circle" />
-                        "Add bill"
+                        {submit_text}
                     </button>
                 </div>
             </form>
         </App>
-    })
-}
-
-async fn add_new_bill(Form(new_bill): Form<NewBillForm>) -> impl IntoResponse {
-    println!("{new_bill:?}");
-
-    ""
+    }
 }
 
-#[derive(serde::Deserialize, Debug)]
-struct NewBillForm {
-    name: String,
-    total: f64,
-    debtee: String,
-    debtors: Vec<String>,
+/// The name of a bill, validated inline whenever it changes.
+#[component]
+fn BillNameField(nobt_id: &str, params: &NewBillParameters, error: Option<&'static str>) -> String {
+    html! {
+        <section class="flex flex-col bg-white p-2" hx-target="this" hx-swap="outerHTML">
+            <h2 class="text-black font-bold text-sm">{t("What did you buy?")}</h2>
+            <input required="true" class="outline-none peer border-b py-2" name="name" value={params.name.clone().unwrap_or_default()} placeholder={t("Trip Snacks, Train Tickets, Beer, ...")} hx-post={format!("/{nobt_id}/bill/validate/name")} hx-trigger="change" />
+            <span class="text-xs text-[grey]">{t("Enter a descriptive name for what was paid for.")}</span>
+            <FieldError message=error />
+        </section>
+    }
 }
 
-async fn choose_bill_debtee(
-    Path(nobt_id): Path<String>,
-    Form(params): Form<NewBillParameters>,
-) -> impl IntoResponse {
-    let title = "Swedish Shenanigans";
-    let _currency = "EUR".to_owned();
-    let back_link = format!("/{nobt_id}/bill");
-    let mut names = HashSet::from([
-        "Thomas".to_owned(),
-        "Simon".to_owned(),
-        "Prada".to_owned(),
-        "Benji".to_owned(),
-    ]);
+/// The day a bill was paid on, validated inline whenever it changes.
+#[component]
+fn BillDateField(nobt_id: &str, params: &NewBillParameters, error: Option<&'static str>) -> String {
+    let date = params.date.clone().unwrap_or_else(|| today().to_string());
 
-    if let Some(debtee) = params.debtee.as_ref() {
-        names.insert(debtee.to_owned());
-    }
-    if let Some(debtors) = params.debtors.clone() {
-        names.extend(debtors.iter().map(|s| s.to_owned()));
+    html! {
+        <section class="flex flex-col bg-white p-2" hx-target="this" hx-swap="outerHTML">
+            <h2 class="text-black font-bold text-sm">{t("When was it paid?")}</h2>
+            <input required="true" class="outline-none peer border-b py-2 bg-white" type="date" name="date" value=date hx-post={format!("/{nobt_id}/bill/validate/date")} hx-trigger="change" />
+            <span class="text-xs text-[grey]">{t("Enter the day the bill was paid on, it defaults to today.")}</span>
+            <FieldError message=error />
+        </section>
     }
+}
 
-    let nobt_id = nobt_id.as_str();
-    let bill_name = params.name.as_deref();
-    let selected_debtee = params.debtee.as_deref();
-    let total = params.total;
-    let debtors = &params.debtors.unwrap_or_default();
+/// What a bill was spent on, either one of the categories used so far or a new one.
+#[component]
+fn BillCategoryField(
+    nobt: &Nobt,
+    params: &NewBillParameters,
+    error: Option<&'static str>,
+) -> String {
+    let selected = params
+        .category
+        .as_deref()
+        .unwrap_or(Category::Other.as_str());
+    let categories = Category::BUILT_IN
+        .iter()
+        .cloned()
+        .chain(
+            nobt.custom_categories()
+                .into_iter()
+                .map(|name| Category::Custom(name.to_owned())),
+        )
+        .collect::<Vec<_>>();
 
-    Html(html! {
-        <App title=title>
-            <Header>
-                <BackLink href=&back_link/>
-                <HeaderTitle title="Select debtee" />
+    html! {
+        <section class="flex flex-col bg-white p-2 gap-2">
+            <h2 class="text-black font-bold text-sm">{t("What kind of expense is it?")}</h2>
+            <div class="flex flex-wrap gap-2">
+                {categories
+                    .iter()
+                    .enumerate()
+                    .map(|(index, category)| {
+                        // Custom categories can contain anything but IDs must not contain spaces, so they are numbered.
+                        let id = format!("category_{index}");
+
+                        html! {
+                            <label class="flex items-center gap-1 px-3 py-1 rounded-full border cursor-pointer hover:bg-hover has-[:checked]:bg-turquoise has-[:checked]:text-white" for={id.clone()}>
+                                {if category.as_str() == selected {
+                                    html! { <input id=id class="hidden" type="radio" name="category" checked="checked" value={category.as_str()}/> }
+                                } else {
+                                    html! { <input id=id class="hidden" type="radio" name="category" value={category.as_str()}/> }
+                                }}
+                                <span class="material-symbols-outlined text-base">{category.icon()}</span>
+                                <span class="text-sm">{category_label(category)}</span>
+                            </label>
+                        }
+                    })
+                    .collect_fragment()}
+            </div>
+            <input class="outline-none peer border-b py-2" name="custom_category" value={params.custom_category.clone().unwrap_or_default()} placeholder={t("Skiing, Souvenirs, Fuel, ...")} />
+            <span class="text-xs text-[grey]">{t("Pick a category or name a new one, which is used instead.")}</span>
+            <FieldError message=error />
+        </section>
+    }
+}
+
+/// The total of a bill and the currency it was paid in, validated inline whenever they change.
+///
+/// Bills paid in another currency than the one of the nobt also need an exchange rate.
+#[component]
+fn BillTotalField(nobt: &Nobt, params: &NewBillParameters, error: Option<&'static str>) -> String {
+    let validate_url = format!("/{}/bill/validate/total", nobt.id);
+    let currency = nobt.currency;
+    let bill_currency = params.currency(nobt);
+
+    html! {
+        <section class="flex flex-col bg-white p-2" hx-target="this" hx-swap="outerHTML">
+            <h2 class="text-black font-bold text-sm">{t("How much did it cost?")}</h2>
+            <div class="flex items-center gap-2">
+                <select class="outline-none border-b py-2 bg-white text-[grey]" name="currency" aria-label="Currency" hx-post=&validate_url hx-trigger="change">
+                    {Currency::ALL
+                        .iter()
+                        .map(|c| if *c == bill_currency {
+                            html! { <option value={c.code()} selected="selected">{c.code()}</option> }
+                        } else {
+                            html! { <option value={c.code()}>{c.code()}</option> }
+                        })
+                        .collect_fragment()}
+                </select>
+                <input required="true" class="outline-none peer border-b py-2 appearance-none w-full" name="total" value={params.total.clone().unwrap_or_default()} step={bill_currency.step()} min="0" type="number" placeholder={Money::zero(bill_currency).to_decimal_string()} hx-post=&validate_url hx-trigger="change" /> // TODO: Don't set 0 by default
+            </div>
+            <span class="text-xs text-[grey]">{t("Enter the total of this bill and the currency it was paid in.")}</span>
+            {if bill_currency != currency {
+                html! {
+                    <div class="flex items-center gap-2">
+                        <label class="text-sm text-[grey] whitespace-nowrap" for="exchange_rate">{format!("1 {bill_currency} =")}</label>
+                        <input id="exchange_rate" class="outline-none border-b py-2 w-full text-right" type="text" inputmode="decimal" name="exchange_rate" value={params.exchange_rate_of(bill_currency).unwrap_or_default()} hx-post=&validate_url hx-trigger="change" />
+                        <input type="hidden" name="rate_currency" value={bill_currency.code()} />
+                        <span class="text-sm text-[grey]">{currency.code()}</span>
+                    </div>
+                    <span class="text-xs text-[grey]">{t("The balances are computed in the currency of this nobt, using this exchange rate.")}</span>
+                }
+            } else {
+                String::new()
+            }}
+            <FieldError message=error />
+        </section>
+    }
+}
+
+#[component]
+fn FieldError(message: Option<&'static str>) -> String {
+    message
+        .map(|message| {
+            html! {
+                <span class="text-xs text-red">{t(message)}</span>
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// The name of a category in the language of the request, custom categories are shown as they were entered.
+fn category_label(category: &Category) -> &str {
+    match category {
+        Category::Custom(name) => name,
+        built_in => t(built_in.label()),
+    }
+}
+
+/// Describes the split mode of a bill in the wizard, e.g. "Split equally.".
+///
+/// These are whole sentences rather than [`SplitMode::label`] in lowercase, as German keeps its nouns capitalized.
+fn split_summary(mode: SplitMode) -> &'static str {
+    t(match mode {
+        SplitMode::Equal => "Split equally.",
+        SplitMode::Shares => "Split by shares.",
+        SplitMode::Percentages => "Split by percentages.",
+        SplitMode::Exact => "Split by exact amounts.",
+    })
+}
+
+/// Describes the split mode of a bill on its page, e.g. "It is split equally.".
+fn split_description(mode: SplitMode) -> &'static str {
+    t(match mode {
+        SplitMode::Equal => "It is split equally.",
+        SplitMode::Shares => "It is split by shares.",
+        SplitMode::Percentages => "It is split by percentages.",
+        SplitMode::Exact => "It is split by exact amounts.",
+    })
+}
+
+async fn add_new_bill(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let new_bill = match params.validate(&nobt) {
+        Ok(new_bill) => new_bill,
+        Err(errors) => {
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Html(bill_wizard(&nobt, &params, &errors).await),
+            )
+                .into_response())
+        }
+    };
+
+    storage.add_bill(&nobt_id, new_bill, actor.as_deref())?;
+
+    Ok(SeeOther(format!("/{nobt_id}")).into_response())
+}
+
+/// Validates a single field of the bill wizard while it is being filled in.
+///
+/// Responds with the field's section, showing the error if the field is invalid.
+async fn validate_bill_field(
+    State(storage): State<Arc<dyn Storage>>,
+    Path((nobt_id, field)): Path<(String, String)>,
+    Form(mut params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    prefill_exchange_rate(&*storage, &nobt, &mut params)?;
+
+    let errors = params.validate(&nobt).err().unwrap_or_default();
+    let nobt_id = nobt_id.as_str();
+    let params = &params;
+    let error = match field.as_str() {
+        "total" => errors.get("total").or(errors.get("exchange_rate")),
+        field => errors.get(field),
+    };
+
+    Ok(match field.as_str() {
+        "name" => Html(html! {
+            <BillNameField nobt_id=nobt_id params=params error=error />
+        })
+        .into_response(),
+        "date" => Html(html! {
+            <BillDateField nobt_id=nobt_id params=params error=error />
+        })
+        .into_response(),
+        "total" => Html(html! {
+            <BillTotalField nobt=&nobt params=params error=error />
+        })
+        .into_response(),
+        _ => return Err(AppError::BadRequest("This field doesn't exist.")),
+    })
+}
+
+async fn choose_bill_debtee(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Form(params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    Ok(bill_debtee_page(&nobt, &params, &FieldErrors::default()).await)
+}
+
+async fn add_bill_debtee(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(mut params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(mut nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let mut errors = FieldErrors::default();
+    if let Some(name) = add_new_participant(
+        &*storage,
+        &mut nobt,
+        actor.as_deref(),
+        &mut params,
+        &mut errors,
+    )? {
+        params.debtee = Some(name);
+        params.debtee_names.clear();
+        params.debtee_amounts.clear();
+
+        return Ok(Html(bill_wizard(&nobt, &params, &errors).await).into_response());
+    }
+
+    Ok(bill_debtee_page(&nobt, &params, &errors).await)
+}
+
+async fn bill_debtee_page(
+    nobt: &Nobt,
+    params: &NewBillParameters,
+    errors: &FieldErrors,
+) -> Response {
+    let nobt_id = nobt.id.as_str();
+    let title = nobt.title.as_str();
+    let back_link = format!("/{nobt_id}/bill");
+    let names = nobt.participant_names().collect::<Vec<_>>();
+    let status = if errors.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+    let zero = &Money::zero(params.currency(nobt)).to_decimal_string();
+
+    let selected_debtee = params.debtee.as_deref();
+
+    (status, Html(html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&back_link/>
+                <HeaderTitle title={t("Select debtees")} />
             </Header>
             <div class="bg-turquoise p-4 flex flex-col gap-4">
                 <section class="flex flex-col bg-white p-2 gap-2">
-                    <h2 class="text-black font-bold text-sm">"Who paid?"</h2>
+                    <h2 class="text-black font-bold text-sm">{t("Who paid?")}</h2>
 
                     {names
                         .iter()
                         .map(|debtee| async move {
-                            let is_current_debtee = selected_debtee.map(|sd| sd == debtee).unwrap_or(false);
+                            let is_current_debtee = selected_debtee.map(|sd| sd == *debtee).unwrap_or(false);
 
                             html! {
-                                <ChooseDebteeForm nobt_id=nobt_id name=bill_name total=total debtee=debtee.as_str() debtors=debtors is_checked=is_current_debtee />
+                                <ChooseDebteeForm nobt_id=nobt_id params=params debtee=debtee is_checked=is_current_debtee />
                             }
                         })
                         .collect_fragment_async().await}
                 </section>
 
                 <section class="flex flex-col bg-white p-2 gap-2">
-                    <h2 class="text-black font-bold text-sm">"Someone else?"</h2>
-
-                    <form method="post" action={format!("/{nobt_id}/bill")} class="w-full flex items-center gap-2">
-                        {bill_name.map(|name| html! {
-                            <input type="hidden" name="name" value=name />
-                        }).unwrap_or_default()}
-                        {total.map(|total| html! {
-                            <input type="hidden" name="total" value={total.to_string()} />
-                        }).unwrap_or_default()}
-                        {debtors
-                            .iter()
-                            .map(|d| html! {
-                                <input type="hidden" name="debtors" value=d/>
-                            })
-                            .collect_fragment()}
-                        <input class="outline-none border-b appearance-none w-full flex-grow p-2 truncate" type="text" name="debtee" placeholder="Bart, Milhouse, Nelson, ..."/>
+                    <h2 class="text-black font-bold text-sm">{t("Someone else?")}</h2>
+
+                    <form method="post" action={format!("/{nobt_id}/bill/debtee")} class="w-full flex items-center gap-2">
+                        <HiddenBillInputs params=params fields=&["name", "date", "category", "total", "debtors", "split", "revision_of"] />
+                        <input class="outline-none border-b appearance-none w-full flex-grow p-2 truncate" type="text" name="new_participant" placeholder="Bart, Milhouse, Nelson, ..."/>
                         <button class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow cursor-pointer">
                             <Icon name="person_add" />
-                            "Add"
+                            {t("Add")}
                         </button>
                     </form>
+                    <span class="text-xs text-[grey]">{t("They will be added to the participants of this nobt.")}</span>
+                    <FieldError message=errors.get("new_participant") />
+                </section>
+
+                <section class="flex flex-col bg-white p-2 gap-2">
+                    <h2 class="text-black font-bold text-sm">{t("Did several people pay?")}</h2>
+
+                    <form method="post" action={format!("/{nobt_id}/bill")}>
+                        <HiddenBillInputs params=params fields=&["name", "date", "category", "total", "debtors", "split", "revision_of"] />
+                        {names
+                            .iter()
+                            .map(|d| async move {
+                                let id = format!("{d}_paid_amount");
+
+                                html! {
+                                    <div class="flex items-center p-2 gap-2">
+                                        <label class="flex-grow flex items-center gap-2" for={id.clone()}>
+                                            <Avatar name=*d />
+                                            {*d}
+                                        </label>
+                                        <input type="hidden" name="debtee_names" value={*d} />
+                                        <input id=id class="outline-none border-b py-2 w-24 text-right" type="text" inputmode="decimal" name="debtee_amounts" value={params.debtee_amount(d).unwrap_or_default()} placeholder=zero />
+                                    </div>
+                                }
+                            })
+                            .collect_fragment_async().await}
+                        <span class="text-xs text-[grey]">{t("Enter how much each person paid. The amounts need to add up to the total of the bill.")}</span>
+
+                        <div class="flex flex-row-reverse">
+                            <button type="submit" class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow w-full justify-center">
+                                <Icon name="check_circle"/>
+                                {t("Set debtees")}
+                            </button>
+                        </div>
+                    </form>
                 </section>
             </div>
         </App>
-    })
+    }))
+    .into_response()
 }
 
 // TODO:
@@ -361,51 +1469,75 @@ async fn choose_bill_debtee(
 // - needs submit button
 // - needs add person button
 async fn choose_bill_debtors(
+    State(storage): State<Arc<dyn Storage>>,
     Path(nobt_id): Path<String>,
     Form(params): Form<NewBillParameters>,
-) -> impl IntoResponse {
-    let title = "Swedish Shenanigans";
-    let _currency = "EUR".to_owned();
-    let back_link = format!("/{nobt_id}/bill");
-    let mut names = HashSet::from([
-        "Thomas".to_owned(),
-        "Simon".to_owned(),
-        "Prada".to_owned(),
-        "Benji".to_owned(),
-    ]);
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
 
-    if let Some(debtee) = params.debtee.as_ref() {
-        names.insert(debtee.to_owned());
-    }
-    if let Some(debtors) = params.debtors.clone() {
-        names.extend(debtors.iter().map(|s| s.to_owned()));
+    Ok(bill_debtors_page(&nobt, &params, &FieldErrors::default()).await)
+}
+
+async fn add_bill_debtor(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(mut params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(mut nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let mut errors = FieldErrors::default();
+    if let Some(name) = add_new_participant(
+        &*storage,
+        &mut nobt,
+        actor.as_deref(),
+        &mut params,
+        &mut errors,
+    )? {
+        if let Some(debtors) = &mut params.debtors {
+            debtors.insert(name);
+        }
     }
 
-    let bill_name = params.name.as_deref();
-    let selected_debtee = params.debtee.as_deref();
-    let total = params.total.as_ref();
-    let debtors = &params.debtors.unwrap_or(names.clone());
+    Ok(bill_debtors_page(&nobt, &params, &errors).await)
+}
 
-    Html(html! {
+async fn bill_debtors_page(
+    nobt: &Nobt,
+    params: &NewBillParameters,
+    errors: &FieldErrors,
+) -> Response {
+    let nobt_id = nobt.id.as_str();
+    let title = nobt.title.as_str();
+    let back_link = format!("/{nobt_id}/bill");
+    let names = nobt.participant_names().collect::<Vec<_>>();
+    let status = if errors.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::UNPROCESSABLE_ENTITY
+    };
+
+    let debtors = &params
+        .debtors
+        .clone()
+        .unwrap_or_else(|| names.iter().map(|name| name.to_string()).collect());
+
+    (status, Html(html! {
         <App title=title>
             <Header>
                 <BackLink href=&back_link/>
-                <HeaderTitle title="Select debtors" />
+                <HeaderTitle title={t("Select debtors")} />
             </Header>
             <div class="bg-turquoise p-4 flex flex-col gap-4">
                 <section class="flex flex-col bg-white p-2 gap-2">
-                    <h2 class="text-black font-bold text-sm">"Who is in?"</h2>
+                    <h2 class="text-black font-bold text-sm">{t("Who is in?")}</h2>
 
                     <form method="post" action={format!("/{nobt_id}/bill")}>
-                        {bill_name.map(|name| html! {
-                            <input type="hidden" name="name" value=name />
-                        }).unwrap_or_default()}
-                        {selected_debtee.map(|debtee| html! {
-                            <input type="hidden" name="debtee" value=debtee />
-                        }).unwrap_or_default()}
-                        {total.map(|total| html! {
-                            <input type="hidden" name="total" value={total.to_string()} />
-                        }).unwrap_or_default()}
+                        <HiddenBillInputs params=params fields=&["name", "date", "category", "debtee", "total", "split", "revision_of"] />
                         {names
                             .iter()
                             .map(|d| async move {
@@ -414,13 +1546,13 @@ async fn choose_bill_debtors(
                                 html! {
                                     <div class="flex items-center hover:bg-hover p-2 cursor-pointer">
                                         <label class="flex-grow flex items-center gap-2" for={id.clone()}>
-                                            <Avatar name=d.as_str() />
-                                            {d.as_str()}
+                                            <Avatar name=*d />
+                                            {*d}
                                         </label>
-                                        {if debtors.contains(d) || debtors.is_empty() {
-                                            html! { <input id=id type="checkbox" name="debtors" checked="checked" value={d.as_str()}/> }
+                                        {if debtors.contains(*d) || debtors.is_empty() {
+                                            html! { <input id=id type="checkbox" name="debtors" checked="checked" value={*d}/> }
                                         } else {
-                                            html! { <input id=id type="checkbox" name="debtors" value={d.as_str()}/> }
+                                            html! { <input id=id type="checkbox" name="debtors" value={*d}/> }
                                         }}
                                     </div>
                                 }
@@ -430,67 +1562,163 @@ async fn choose_bill_debtors(
                         <div class="flex flex-row-reverse">
                             <button type="submit" class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow w-full justify-center"> // TODO: Make this a button component.
                                 <Icon name="check_circle"/>
-                                "Set debtors"
+                                {t("Set debtors")}
                             </button>
                         </div>
                     </form>
                 </section>
 
                 <section class="flex flex-col bg-white p-2 gap-2">
-                    <h2 class="text-black font-bold text-sm">"Someone else?"</h2>
+                    <h2 class="text-black font-bold text-sm">{t("Someone else?")}</h2>
 
                     <form method="post" action={format!("/{nobt_id}/bill/debtors")} class="w-full flex items-center gap-2">
-                        {bill_name.map(|name| html! {
-                            <input type="hidden" name="name" value=name />
-                        }).unwrap_or_default()}
-                        {selected_debtee.map(|debtee| html! {
-                            <input type="hidden" name="debtee" value=debtee />
-                        }).unwrap_or_default()}
-                        {total.map(|total| html! {
-                            <input type="hidden" name="total" value={total.to_string()} />
-                        }).unwrap_or_default()}
+                        <HiddenBillInputs params=params fields=&["name", "date", "category", "debtee", "total", "split", "revision_of"] />
                         {debtors
                             .iter()
                             .map(|d| html! {
                                 <input type="hidden" name="debtors" value=d/>
                             })
                             .collect_fragment()}
-                        <input class="outline-none border-b appearance-none w-full flex-grow p-2 truncate" type="text" name="debtors" placeholder="Bart, Milhouse, Nelson, ..."/>
+                        <input class="outline-none border-b appearance-none w-full flex-grow p-2 truncate" type="text" name="new_participant" placeholder="Bart, Milhouse, Nelson, ..."/>
                         <button class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow">
                             <Icon name="person_add" />
-                            "Add"
+                            {t("Add")}
                         </button>
                     </form>
+                    <span class="text-xs text-[grey]">{t("They will be added to the participants of this nobt.")}</span>
+                    <FieldError message=errors.get("new_participant") />
                 </section>
             </div>
         </App>
+    }))
+    .into_response()
+}
+
+async fn choose_bill_split(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Form(params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let title = nobt.title.as_str();
+    let back_link = format!("/{nobt_id}/bill");
+    let params = &params;
+    let selected_mode = params.split_mode();
+    let debtors = nobt
+        .participant_names()
+        .filter(|name| {
+            params
+                .debtors
+                .as_ref()
+                .is_none_or(|debtors| debtors.contains(*name))
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Html(html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&back_link/>
+                <HeaderTitle title={t("Split the bill")} />
+            </Header>
+            <form method="post" action={format!("/{nobt_id}/bill")} class="bg-turquoise p-4 flex flex-col gap-4">
+                <HiddenBillInputs params=params fields=&["name", "date", "category", "total", "debtee", "debtors", "revision_of"] />
+                <section class="flex flex-col bg-white p-2 gap-2">
+                    <h2 class="text-black font-bold text-sm">{t("How is it split?")}</h2>
+
+                    {SplitMode::ALL
+                        .iter()
+                        .map(|mode| {
+                            let id = format!("{}_split_mode", mode.as_str());
+
+                            html! {
+                                <label class="flex items-center hover:bg-hover p-2 gap-2 cursor-pointer" for={id.clone()}>
+                                    <span class="flex-grow">{t(mode.label())}</span>
+                                    {if *mode == selected_mode {
+                                        html! { <input id=id type="radio" name="split_mode" checked="checked" value={mode.as_str()}/> }
+                                    } else {
+                                        html! { <input id=id type="radio" name="split_mode" value={mode.as_str()}/> }
+                                    }}
+                                </label>
+                            }
+                        })
+                        .collect_fragment()}
+                </section>
+
+                <section class="flex flex-col bg-white p-2 gap-2">
+                    <h2 class="text-black font-bold text-sm">{t("Who pays how much?")}</h2>
+
+                    {debtors
+                        .iter()
+                        .map(|d| async move {
+                            let id = format!("{d}_split_value");
+
+                            html! {
+                                <div class="flex items-center p-2 gap-2">
+                                    <label class="flex-grow flex items-center gap-2" for={id.clone()}>
+                                        <Avatar name=*d />
+                                        {*d}
+                                    </label>
+                                    <input type="hidden" name="split_names" value={*d} />
+                                    <input id=id class="outline-none border-b py-2 w-24 text-right" type="text" inputmode="decimal" name="split_values" value={params.split_value(d).unwrap_or_default()} />
+                                </div>
+                            }
+                        })
+                        .collect_fragment_async().await}
+                    <span class="text-xs text-[grey]">{t("Enter the number of shares, the percentage or the exact amount of each person. Nothing needs to be entered when splitting equally.")}</span>
+                </section>
+
+                <div>
+                    <button type="submit" class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow w-full justify-center bg-white">
+                        <Icon name="check_circle"/>
+                        {t("Set split")}
+                    </button>
+                </div>
+            </form>
+        </App>
     })
+    .into_response())
+}
+
+/// Adds the participant entered in one of the "Someone else?" forms of the bill wizard, if any.
+///
+/// Returns the name of the participant, so that the step can select them right away.
+fn add_new_participant(
+    storage: &dyn Storage,
+    nobt: &mut Nobt,
+    actor: Option<&str>,
+    params: &mut NewBillParameters,
+    errors: &mut FieldErrors,
+) -> Result<Option<String>> {
+    let Some(name) = params.new_participant.take() else {
+        return Ok(None);
+    };
+    let Some(name) = errors.check("new_participant", validation::person_name(&name)) else {
+        return Ok(None);
+    };
+
+    if storage.add_participant(&nobt.id, name, actor)? {
+        nobt.participants.push(Participant {
+            name: name.to_owned(),
+        });
+    }
+
+    Ok(Some(name.to_owned()))
 }
 
 #[component]
 fn ChooseDebteeForm(
     nobt_id: &str,
-    name: Option<&'a str>,
-    total: Option<f64>,
+    params: &NewBillParameters,
     debtee: &str,
-    debtors: &HashSet<String>,
     is_checked: bool,
 ) -> String {
     html! {
         <form method="post" action={format!("/{nobt_id}/bill")} class="w-full">
             <input type="hidden" name="debtee" value=&debtee />
-            {name.map(|name| html! {
-                <input type="hidden" name="name" value=name />
-            }).unwrap_or_default()}
-            {total.map(|total| html! {
-                <input type="hidden" name="total" value={total.to_string()} />
-            }).unwrap_or_default()}
-            {debtors
-                .iter()
-                .map(|d| html! {
-                    <input type="hidden" name="debtors" value=d/>
-                })
-                .collect_fragment()}
+            <HiddenBillInputs params=params fields=&["name", "date", "category", "total", "debtors", "split", "revision_of"] />
             <button class="flex items-center hover:bg-hover gap-2 p-2 cursor-pointer w-full">
                 <Avatar name=debtee />
                 <span class="flex-grow text-left">{debtee}</span>
@@ -504,6 +1732,96 @@ fn ChooseDebteeForm(
     }
 }
 
+/// Hidden inputs that carry the given fields of the bill wizard from one step to the next.
+///
+/// `total` also stands for the currency and exchange rate of the total, `debtee` for the single debtee or the amounts entered for several debtees,
+/// `split` for the split mode plus the values entered for each debtor
+/// and `revision_of` for the bill that is being edited.
+#[component]
+fn HiddenBillInputs(params: &NewBillParameters, fields: &[&'a str]) -> String {
+    let revision_of = params.revision_of.map(|id| id.to_string());
+    let mut inputs = Vec::<(&str, &str)>::new();
+
+    for field in fields {
+        match *field {
+            "name" => inputs.extend(params.name.as_deref().map(|name| ("name", name))),
+            "date" => inputs.extend(params.date.as_deref().map(|date| ("date", date))),
+            "category" => {
+                inputs.extend(
+                    params
+                        .category
+                        .as_deref()
+                        .map(|category| ("category", category)),
+                );
+                inputs.extend(
+                    params
+                        .custom_category
+                        .as_deref()
+                        .map(|name| ("custom_category", name)),
+                );
+            }
+            "total" => {
+                inputs.extend(params.total.as_deref().map(|total| ("total", total)));
+                inputs.extend(
+                    params
+                        .currency
+                        .as_deref()
+                        .map(|currency| ("currency", currency)),
+                );
+                inputs.extend(
+                    params
+                        .exchange_rate
+                        .as_deref()
+                        .map(|rate| ("exchange_rate", rate)),
+                );
+                inputs.extend(
+                    params
+                        .rate_currency
+                        .as_deref()
+                        .map(|currency| ("rate_currency", currency)),
+                );
+            }
+            "debtee" => {
+                inputs.extend(params.debtee.as_deref().map(|debtee| ("debtee", debtee)));
+                for (name, amount) in params.debtee_names.iter().zip(&params.debtee_amounts) {
+                    inputs.push(("debtee_names", name));
+                    inputs.push(("debtee_amounts", amount));
+                }
+            }
+            "debtors" => inputs.extend(
+                params
+                    .debtors
+                    .iter()
+                    .flatten()
+                    .map(|d| ("debtors", d.as_str())),
+            ),
+            "revision_of" => inputs.extend(revision_of.as_deref().map(|id| ("revision_of", id))),
+            "split" => {
+                inputs.extend(
+                    params
+                        .split_mode
+                        .as_deref()
+                        .map(|mode| ("split_mode", mode)),
+                );
+                for (name, value) in params.split_names.iter().zip(&params.split_values) {
+                    inputs.push(("split_names", name));
+                    inputs.push(("split_values", value));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    inputs
+        .into_iter()
+        .map(|(name, value)| {
+            html! {
+                <input type="hidden" name=name value=value />
+            }
+        })
+        .collect_fragment()
+}
+
 // #[component]
 // fn PersonRadiobox(name: &str, required: bool) -> String {
 //     let id = format!("{name}_debtee");
@@ -538,42 +1856,37 @@ fn ChooseDebteeForm(
 //     }
 // }
 
-async fn balances(Path(nobt_id): Path<String>) -> impl IntoResponse {
-    let title = "Swedish Shenanigans";
-    let currency = "EUR";
-    let nobt_url = format!("/{nobt_id}");
+async fn balances(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
 
-    let balances = vec![
-        BalanceItem {
-            name: "Simon".to_string(),
-            amount: -99.66,
-            url: format!("/{nobt_id}/balances/Simon"),
-        },
-        BalanceItem {
-            name: "Thomas".to_string(),
-            amount: 390.34,
-            url: format!("/{nobt_id}/balances/Thomas"),
-        },
-        BalanceItem {
-            name: "Prada".to_string(),
-            amount: -290.68,
-            url: format!("/{nobt_id}/balances/Prada"),
-        },
-        BalanceItem {
-            name: "Beji".to_string(),
-            amount: 0.00,
-            url: format!("/{nobt_id}/balances/Benji"),
-        },
-    ];
+    let title = nobt.title.as_str();
+    let nobt_url = format!("/{nobt_id}");
 
-    Html(html! {
+    let balances = balance::balances(&nobt)
+        .into_iter()
+        .map(|balance| BalanceItem {
+            url: format!(
+                "/{nobt_id}/balances/{}",
+                format::path_segment(&balance.name)
+            ),
+            name: balance.name,
+            amount: balance.amount,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Html(html! {
         <App title=title>
             <Header>
                 <BackLink href=&nobt_url/>
-                <HeaderTitle title="Balances" />
+                <HeaderTitle title={t("Balances")} />
             </Header>
             <div class="bg-white p-4">
-                <Section title="Balance overview" subtitle="The balances of all users in this Nobt.">
+                <Section title={t("Balance overview")} subtitle={t("The balances of all users in this Nobt.")}>
                     <List>
                         {balances
                             .iter()
@@ -583,7 +1896,7 @@ async fn balances(Path(nobt_id): Path<String>) -> impl IntoResponse {
                                         <Avatar name=&balance.name />
                                         <span class="grow flex flex-col">
                                             <span>{balance.name.as_str()}</span>
-                                            <ThemedAmount currency=currency value=balance.amount />
+                                            <ThemedAmount value=balance.amount />
                                         </span>
                                     </LinkListItem>
                                 }
@@ -594,119 +1907,769 @@ async fn balances(Path(nobt_id): Path<String>) -> impl IntoResponse {
             </div>
         </App>
     })
+    .into_response())
+}
+
+async fn individual_balance(
+    State(storage): State<Arc<dyn Storage>>,
+    Path((nobt_id, name)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    // Former participants are still listed on the balances page as long as they owe or get money.
+    let balances = balance::balances(&nobt);
+    if !balances.iter().any(|b| b.name == name) {
+        return Err(AppError::ParticipantNotFound);
+    }
+
+    let title = nobt.title.as_str();
+    let back_url = format!("/{nobt_id}/balances");
+
+    let paid_bills = nobt
+        .active_bills()
+        .filter(|b| b.is_debtee(&name))
+        .collect::<Vec<_>>();
+    let paid_sum = paid_bills
+        .iter()
+        .copied()
+        .flat_map(Bill::paid_amounts)
+        .filter(|(debtee, _)| *debtee == name)
+        .fold(Money::zero(nobt.currency), |sum, (_, amount)| sum + amount);
+    let num_participating = nobt.active_bills().filter(|b| b.is_debtor(&name)).count();
+    let num_bills = nobt.active_bills().count();
+    let balance = balances
+        .iter()
+        .find(|b| b.name == name)
+        .map(|b| b.amount)
+        .unwrap_or(Money::zero(nobt.currency));
+
+    // Debts are negative, money the participant gets back is positive.
+    let debts = settlement::settle(&balances)
+        .into_iter()
+        .filter_map(|transfer| {
+            if transfer.from == name {
+                Some(DebtItem {
+                    name: transfer.to,
+                    amount: -transfer.amount,
+                })
+            } else if transfer.to == name {
+                Some(DebtItem {
+                    name: transfer.from,
+                    amount: transfer.amount,
+                })
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    let debt_sum = debts
+        .iter()
+        .fold(Money::zero(nobt.currency), |sum, d| sum + d.amount);
+    let debts_subtitle = match (debt_sum.is_negative(), debt_sum.is_positive(), debts.len()) {
+        (true, _, 1) => tf(
+            "{name} owes {amount} to 1 person.",
+            &[("name", &name), ("amount", &-debt_sum)],
+        ),
+        (true, _, num) => tf(
+            "{name} owes {amount} to {num} persons.",
+            &[("name", &name), ("amount", &-debt_sum), ("num", &num)],
+        ),
+        (_, true, 1) => tf(
+            "{name} gets {amount} from 1 person.",
+            &[("name", &name), ("amount", &debt_sum)],
+        ),
+        (_, true, num) => tf(
+            "{name} gets {amount} from {num} persons.",
+            &[("name", &name), ("amount", &debt_sum), ("num", &num)],
+        ),
+        _ => tf("{name} is settled up.", &[("name", &name)]),
+    };
+
+    Ok(Html(html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&back_url />
+                <HeaderTitle title=&name />
+            </Header>
+            <div class="bg-white p-4 flex flex-col gap-4">
+                <Section title={t("Summary")} subtitle="">
+                    <List>
+                        <ListItem>
+                            <ListItemIcon name="account_balance_wallet"/>
+                            <span class="grow">{tf("{name}'s balance", &[("name", &name)])}</span>
+                            <ThemedAmount value=balance />
+                        </ListItem>
+                        <ListItem>
+                            <ListItemIcon name="info"/>
+                            {match paid_bills.len() {
+                                1 => tf("{name} paid 1 bill ({amount}).", &[("name", &name), ("amount", &paid_sum)]),
+                                num => tf("{name} paid {num} bills ({amount}).", &[("name", &name), ("num", &num), ("amount", &paid_sum)]),
+                            }}
+                        </ListItem>
+                        <ListItem>
+                            <ListItemIcon name="info"/>
+                            {tf("{name} participates in {num} of {total} bills.", &[("name", &name), ("num", &num_participating), ("total", &num_bills)])}
+                        </ListItem>
+                    </List>
+                </Section>
+                <Section title={t("Debts")} subtitle=&debts_subtitle>
+                    <List>
+                        {debts
+                            .iter()
+                            .map(|debt| async {
+                            html! {
+                                <ListItem>
+                                    <Avatar name=&debt.name />
+                                    <span class="grow flex flex-col">
+                                        <span>{debt.name.as_str()}</span>
+                                        <ThemedAmount value=debt.amount />
+                                    </span>
+                                </ListItem>
+                            }
+                        })
+                            .collect_fragment_async().await}
+                    </List>
+                </Section>
+            </div>
+        </App>
+    })
+    .into_response())
+}
+
+async fn settle_up(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let title = nobt.title.as_str();
+    let nobt_url = format!("/{nobt_id}");
+
+    let transfers = settlement::settle(&balance::balances(&nobt));
+    let subtitle = match transfers.len() {
+        0 => t("Everyone is settled up.").to_owned(),
+        1 => t("1 transfer settles all debts in this Nobt.").to_owned(),
+        num => tf(
+            "{num} transfers settle all debts in this Nobt.",
+            &[("num", &num)],
+        ),
+    };
+
+    Ok(Html(html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&nobt_url/>
+                <HeaderTitle title={t("Settle up")} />
+            </Header>
+            <div class="bg-white p-4">
+                <Section title={t("How to settle up")} subtitle=&subtitle>
+                    <List>
+                        {transfers
+                            .iter()
+                            .map(|transfer| async {
+                                html! {
+                                    <ListItem>
+                                        <Avatar name=&transfer.from />
+                                        <span class="grow flex flex-col">
+                                            <span>{tf("{from} pays {to}", &[("from", &transfer.from), ("to", &transfer.to)])}</span>
+                                            <Amount value=transfer.amount classes="text-darkGrey"/>
+                                        </span>
+                                        <Avatar name=&transfer.to />
+                                    </ListItem>
+                                }
+                            })
+                            .collect_fragment_async().await}
+                    </List>
+                </Section>
+            </div>
+        </App>
+    })
+    .into_response())
+}
+
+async fn participants(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    Ok(Html(participants_page(&nobt, &FieldErrors::default()).await).into_response())
+}
+
+/// Lists the participants of a nobt with their balances, to add, rename or remove them.
+///
+/// Only participants who are settled up can be removed, so that no debts get lost.
+async fn participants_page(nobt: &Nobt, errors: &FieldErrors) -> String {
+    let nobt_id = nobt.id.as_str();
+    let title = nobt.title.as_str();
+    let nobt_url = format!("/{nobt_id}");
+    let balances = balance::balances(nobt);
+    let participants = nobt
+        .participant_names()
+        .map(|name| {
+            let amount = balances
+                .iter()
+                .find(|b| b.name == name)
+                .map(|b| b.amount)
+                .unwrap_or(Money::zero(nobt.currency));
+
+            (name, amount)
+        })
+        .collect::<Vec<_>>();
+    let names = nobt.participant_names().collect::<Vec<_>>();
+
+    html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&nobt_url/>
+                <HeaderTitle title={t("Participants")} />
+            </Header>
+            <div class="bg-white p-4 flex flex-col gap-8">
+                <Section title={t("Participants")} subtitle={t("Everyone who shares bills in this Nobt. Participants can only be removed once they are settled up.")}>
+                    <List>
+                        {participants
+                            .iter()
+                            .map(|(name, amount)| async move {
+                                html! {
+                                    <ListItem>
+                                        <Avatar name=name />
+                                        <form method="post" action={format!("/{nobt_id}/participants/rename")} class="grow flex items-center gap-2">
+                                            <input type="hidden" name="name" value=name />
+                                            <input class="outline-none border-b appearance-none w-full flex-grow p-2 truncate" type="text" name="new_name" value=name aria-label={tf("New name of {name}", &[("name", name)])} />
+                                            <button type="submit" class="material-symbols-outlined text-darkGrey hover:bg-hover p-2" title={t("Rename")}>"edit"</button>
+                                        </form>
+                                        <ThemedAmount value=*amount />
+                                        {if amount.is_zero() {
+                                            html! {
+                                                <form method="post" action={format!("/{nobt_id}/participants/remove")} hx-confirm={tf("Remove {name} from this nobt?", &[("name", name)])}>
+                                                    <input type="hidden" name="name" value=name />
+                                                    <button type="submit" class="material-symbols-outlined text-darkGrey hover:bg-hover p-2" title={t("Remove")}>"person_remove"</button>
+                                                </form>
+                                            }
+                                        } else {
+                                            String::new()
+                                        }}
+                                    </ListItem>
+                                }
+                            })
+                            .collect_fragment_async().await}
+                    </List>
+                    <FieldError message=errors.get("rename") />
+                    <FieldError message=errors.get("remove") />
+                </Section>
+                <Section title={t("Someone missing?")} subtitle="">
+                    <form method="post" action={format!("/{nobt_id}/participants")} class="w-full flex items-center gap-2">
+                        <input class="outline-none border-b appearance-none w-full flex-grow p-2 truncate" type="text" name="name" placeholder="Bart, Milhouse, Nelson, ..."/>
+                        <button class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow cursor-pointer">
+                            <Icon name="person_add" />
+                            {t("Add")}
+                        </button>
+                    </form>
+                    <FieldError message=errors.get("name") />
+                </Section>
+                {if names.len() > 1 {
+                    html! {
+                        <Section title={t("Same person twice?")} subtitle={t("Merge participants who were added under different names. You can check the changes before merging.")}>
+                            <form method="get" action={format!("/{nobt_id}/participants/merge")} class="flex flex-col gap-2">
+                                <label class="flex flex-col text-sm">
+                                    {t("Merge")}
+                                    <ParticipantSelect name="name" participants=&names />
+                                </label>
+                                <label class="flex flex-col text-sm">
+                                    {t("into")}
+                                    <ParticipantSelect name="into" participants=&names />
+                                </label>
+                                <div>
+                                    <button class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow cursor-pointer">
+                                        <Icon name="merge" />
+                                        {t("Preview merge")}
+                                    </button>
+                                </div>
+                            </form>
+                        </Section>
+                    }
+                } else {
+                    String::new()
+                }}
+            </div>
+        </App>
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct ParticipantForm {
+    name: String,
+}
+
+#[derive(serde::Deserialize)]
+struct MergeParticipantsForm {
+    name: String,
+    into: String,
+}
+
+#[derive(serde::Deserialize)]
+struct RenameParticipantForm {
+    name: String,
+    new_name: String,
+}
+
+async fn add_participant(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(form): Form<ParticipantForm>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let mut errors = FieldErrors::default();
+    if let Some(name) = errors.check("name", validation::person_name(&form.name)) {
+        if storage.add_participant(&nobt_id, name, actor.as_deref())? {
+            return Ok(SeeOther(format!("/{nobt_id}/participants")).into_response());
+        }
+        errors.add(
+            "name",
+            "Someone with this name is already part of this nobt.",
+        );
+    }
+
+    Ok((
+        StatusCode::UNPROCESSABLE_ENTITY,
+        Html(participants_page(&nobt, &errors).await),
+    )
+        .into_response())
+}
+
+/// Renames a participant, which also renames them in every bill and payment of the nobt.
+async fn rename_participant(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(form): Form<RenameParticipantForm>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    if !nobt.has_participant(&form.name) {
+        return Err(AppError::ParticipantNotFound);
+    }
+
+    let mut errors = FieldErrors::default();
+    if let Some(new_name) = errors.check("rename", validation::person_name(&form.new_name)) {
+        if new_name == form.name {
+            return Ok(SeeOther(format!("/{nobt_id}/participants")).into_response());
+        }
+        if nobt.has_participant(new_name) {
+            errors.add(
+                "rename",
+                "Someone with this name is already part of this nobt.",
+            );
+        } else if nobt.is_name_used(new_name)
+            || !storage.rename_participant(&nobt_id, &form.name, new_name, actor.as_deref())?
+        {
+            errors.add(
+                "rename",
+                "Bills or payments of a former participant use this name, pick another one.",
+            );
+        } else {
+            return Ok(SeeOther(format!("/{nobt_id}/participants")).into_response());
+        }
+    }
+
+    Ok((
+        StatusCode::UNPROCESSABLE_ENTITY,
+        Html(participants_page(&nobt, &errors).await),
+    )
+        .into_response())
+}
+
+async fn remove_participant(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(form): Form<ParticipantForm>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    if !nobt.has_participant(&form.name) {
+        return Err(AppError::ParticipantNotFound);
+    }
+
+    let is_settled_up = balance::balances(&nobt)
+        .iter()
+        .all(|b| b.name != form.name || b.amount.is_zero());
+    if !is_settled_up {
+        let mut errors = FieldErrors::default();
+        errors.add(
+            "remove",
+            "Only participants who are settled up can be removed.",
+        );
+
+        return Ok((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Html(participants_page(&nobt, &errors).await),
+        )
+            .into_response());
+    }
+
+    storage.remove_participant(&nobt_id, &form.name, actor.as_deref())?;
+
+    Ok(SeeOther(format!("/{nobt_id}/participants")).into_response())
+}
+
+/// Shows how the balances change when merging one participant into another, so that it can be confirmed.
+async fn preview_merge(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Form(form): Form<MergeParticipantsForm>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    if !nobt.has_participant(&form.name) || !nobt.has_participant(&form.into) {
+        return Err(AppError::ParticipantNotFound);
+    }
+    if form.name == form.into {
+        return Err(AppError::BadRequest(
+            "A participant cannot be merged into themselves.",
+        ));
+    }
+
+    let mut merged = nobt.clone();
+    merged.merge_participants(&form.name, &form.into);
+    let balances_after = balance::balances(&merged);
+    let changes = balance::balances(&nobt)
+        .into_iter()
+        .filter_map(|before| {
+            let after = balances_after
+                .iter()
+                .find(|b| b.name == before.name)
+                .map(|b| b.amount);
+
+            (after != Some(before.amount)).then_some((before.name, before.amount, after))
+        })
+        .collect::<Vec<_>>();
+    let num_bills = nobt
+        .current_bills()
+        .filter(|b| b.is_debtee(&form.name) || b.is_debtor(&form.name))
+        .count();
+    let num_payments = nobt
+        .payments
+        .iter()
+        .filter(|p| p.from == form.name || p.to == form.name)
+        .count();
+
+    let title = nobt.title.as_str();
+    let participants_url = format!("/{nobt_id}/participants");
+    let (name, into) = (form.name.as_str(), form.into.as_str());
+    let summary = tf(
+        "{into} takes over {bills} and {payments} of {name}. Payments between the two are dropped.",
+        &[
+            ("into", &into),
+            ("name", &name),
+            (
+                "bills",
+                &match num_bills {
+                    1 => t("1 bill").to_owned(),
+                    num => tf("{num} bills", &[("num", &num)]),
+                },
+            ),
+            (
+                "payments",
+                &match num_payments {
+                    1 => t("1 payment").to_owned(),
+                    num => tf("{num} payments", &[("num", &num)]),
+                },
+            ),
+        ],
+    );
+
+    Ok(Html(html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&participants_url/>
+                <HeaderTitle title={t("Merge participants")} />
+            </Header>
+            <div class="bg-white p-4 flex flex-col gap-8">
+                <Section title={tf("Merge {name} into {into}?", &[("name", &name), ("into", &into)])} subtitle=summary>
+                    {if changes.is_empty() {
+                        html! {
+                            <p class="text-sm">{t("Nobody's balance changes.")}</p>
+                        }
+                    } else {
+                        html! {
+                            <List>
+                                {changes
+                                    .iter()
+                                    .map(|(person, before, after)| async move {
+                                        html! {
+                                            <ListItem>
+                                                <Avatar name=person />
+                                                <span class="grow flex flex-col">
+                                                    <span>{person.as_str()}</span>
+                                                    <span class="flex items-center gap-2">
+                                                        <ThemedAmount value=*before />
+                                                        <Icon name="arrow_forward" />
+                                                        {match after {
+                                                            Some(after) => html! { <ThemedAmount value=*after /> },
+                                                            None => html! { <span class="text-darkGrey">{tf("merged into {into}", &[("into", &into)])}</span> },
+                                                        }}
+                                                    </span>
+                                                </span>
+                                            </ListItem>
+                                        }
+                                    })
+                                    .collect_fragment_async().await}
+                            </List>
+                        }
+                    }}
+                </Section>
+                <form method="post" action={format!("/{nobt_id}/participants/merge")}>
+                    <input type="hidden" name="name" value=name />
+                    <input type="hidden" name="into" value=into />
+                    <button class="flex items-center justify-center gap-2 text-white uppercase rounded shadow px-4 py-2 bg-darkGreen" type="submit">
+                        <Icon name="merge" />
+                        {t("Merge")}
+                    </button>
+                </form>
+            </div>
+        </App>
+    })
+    .into_response())
+}
+
+async fn merge_participants(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(form): Form<MergeParticipantsForm>,
+) -> Result<Response, AppError> {
+    if !storage.merge_participants(&nobt_id, &form.name, &form.into, actor.as_deref())? {
+        return Err(AppError::ParticipantNotFound);
+    }
+
+    Ok(SeeOther(format!("/{nobt_id}/participants")).into_response())
+}
+
+async fn new_payment(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let title = nobt.title.as_str();
+    let nobt_url = format!("/{nobt_id}");
+    let names = nobt.participant_names().collect::<Vec<_>>();
+
+    Ok(Html(html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&nobt_url/>
+                <HeaderTitle title={t("Pay someone")} />
+            </Header>
+            <form class="bg-turquoise p-4 flex flex-col gap-4" method="post" action={format!("/{nobt_id}/payment")}>
+                <section class="flex flex-col bg-white p-2">
+                    <h2 class="text-black font-bold text-sm">{t("Who paid?")}</h2>
+                    <ParticipantSelect name="from" participants=&names />
+                    <span class="text-xs text-[grey]">{t("Select the person who sent the money.")}</span>
+                </section>
+                <section class="flex flex-col bg-white p-2">
+                    <h2 class="text-black font-bold text-sm">{t("Who received the money?")}</h2>
+                    <ParticipantSelect name="to" participants=&names />
+                    <span class="text-xs text-[grey]">{t("Select the person who got the money.")}</span>
+                </section>
+                <section class="flex flex-col bg-white p-2">
+                    <h2 class="text-black font-bold text-sm">{t("How much was paid?")}</h2>
+                    <div class="flex items-center">
+                        <span class="w-10 h-10 text-[grey] flex items-center justify-center text-xl">{nobt.currency.symbol()}</span>
+                        <input required="true" class="outline-none peer border-b py-2 appearance-none w-full" name="amount" step={nobt.currency.step()} min="0" type="number" placeholder={Money::zero(nobt.currency).to_decimal_string()} />
+                    </div>
+                    <span class="text-xs text-[grey]">{t("Enter the amount that was transferred.")}</span>
+                </section>
+                <section class="flex flex-col bg-white p-2">
+                    <h2 class="text-black font-bold text-sm">{t("What was it for?")}</h2>
+                    <input class="outline-none peer border-b py-2" name="note" placeholder={t("Settling up, Cash for the taxi, ...")} />
+                    <span class="text-xs text-[grey]">{t("Optionally add a note to this payment.")}</span>
+                </section>
+                <div>
+                    <button class="flex items-center justify-center gap-2 text-white uppercase rounded shadow px-4 py-2 bg-darkGreen" type="submit">
+                        <Icon name="check_circle" />
+                        {t("Add payment")}
+                    </button>
+                </div>
+            </form>
+        </App>
+    })
+    .into_response())
 }
 
-async fn individual_balance(Path((nobt_id, name)): Path<(String, String)>) -> impl IntoResponse {
-    let title = "Swedish Shenanigans";
-    let currency = "EUR";
-    let back_url = format!("/{nobt_id}/balances");
+async fn add_payment(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+    Form(form): Form<PaymentForm>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
 
-    let debts = vec![
-        DebtItem {
-            name: "Prada".to_string(),
-            amount: 290.68,
-        },
-        DebtItem {
-            name: "Simon".to_string(),
-            amount: 99.66,
-        },
-    ];
-    let debt_sum = debts.iter().map(|d| d.amount).sum();
-    let debts_subtitle = format!(
-        "{name} owes {} to {} person{}.",
-        format_amount(currency, debt_sum),
-        debts.len(),
-        if debts.len() > 1 { "s" } else { "" }
-    );
+    let new_payment = match form.validate(&nobt) {
+        Ok(new_payment) => new_payment,
+        Err(e) => return Err(AppError::BadRequest(e)),
+    };
 
-    Html(html! {
-        <App title=title>
-            <Header>
-                <BackLink href=&back_url />
-                <HeaderTitle title=&name />
-            </Header>
-            <div class="bg-white p-4 flex flex-col gap-4">
-                <Section title="Summary" subtitle="">
-                    <List>
-                        <ListItem>
-                            <ListItemIcon name="info"/>
-                            {format!("{name} paid 2 bills ({}).", format_amount(currency, 1705.0))}
-                        </ListItem>
-                        <ListItem>
-                            <ListItemIcon name="info"/>
-                            {format!("{name} participates in 13 of 14 bills.")}
-                        </ListItem>
-                    </List>
-                </Section>
-                <Section title="Debts" subtitle=&debts_subtitle>
-                    <List>
-                        {debts
-                            .iter()
-                            .map(|debt| async {
-                            html! {
-                                <ListItem>
-                                    <Avatar name=&debt.name />
-                                    <span class="grow flex flex-col">
-                                        <span>{debt.name.as_str()}</span>
-                                        <ThemedAmount currency=currency value=debt.amount />
-                                    </span>
-                                </ListItem>
-                            }
-                        })
-                            .collect_fragment_async().await}
-                    </List>
-                </Section>
-            </div>
-        </App>
-    })
+    storage.add_payment(&nobt_id, new_payment, actor.as_deref())?;
+
+    Ok(SeeOther(format!("/{nobt_id}")).into_response())
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct PaymentForm {
+    from: String,
+    to: String,
+    amount: String,
+    #[serde(default)]
+    note: String,
+}
+
+impl PaymentForm {
+    fn validate(self, nobt: &Nobt) -> Result<NewPayment, &'static str> {
+        if !nobt.has_participant(&self.from) || !nobt.has_participant(&self.to) {
+            return Err("Payments can only be made between participants of the nobt.");
+        }
+        if self.from == self.to {
+            return Err("Nobody can pay themselves.");
+        }
+        let amount = match Money::parse(&self.amount, nobt.currency) {
+            Some(amount) if amount.is_positive() => amount,
+            None if Money::is_too_large(&self.amount, nobt.currency) => {
+                return Err(AMOUNT_TOO_LARGE)
+            }
+            _ => return Err("The amount of a payment must be a positive amount."),
+        };
+
+        let note = self.note.trim();
+
+        Ok(NewPayment {
+            from: self.from,
+            to: self.to,
+            amount,
+            note: (!note.is_empty()).then(|| note.to_owned()),
+        })
+    }
+}
+
+#[component]
+fn ParticipantSelect(name: &'static str, participants: &[&'a str]) -> String {
+    html! {
+        <select required="true" class="outline-none peer border-b py-2 bg-white" name=name>
+            {participants
+                .iter()
+                .map(|participant| html! {
+                    <option value=participant>{participant}</option>
+                })
+                .collect_fragment()}
+        </select>
+    }
 }
 
-async fn expense(Path((nobt_id, expense_id)): Path<(String, u64)>) -> impl IntoResponse {
-    let title = "Swedish Shenanigans";
-    let name = "Flughafen Essen";
+async fn expense(
+    State(storage): State<Arc<dyn Storage>>,
+    Path((nobt_id, expense_id)): Path<(String, u64)>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    let Some(bill) = nobt.bill(expense_id) else {
+        return Err(AppError::BillNotFound);
+    };
+
+    let title = nobt.title.as_str();
+    let name = bill.name.as_str();
     let nobt_url = format!("/{nobt_id}");
-    let deleted = false;
+    let deleted = bill.deleted;
     let delete_url = format!("/{nobt_id}/{expense_id}/delete");
-    let debtee_name = "Thomas".to_owned();
-    let currency = "EUR";
-    let added_on = "28 August 2022".to_owned();
-    let total = "EUR 39.00".to_owned();
-
-    let debtors = vec![
-        DebtorItem {
-            name: "Simon".to_string(),
-            amount_owed: -19.50,
-        },
-        DebtorItem {
-            name: "Thomas".to_string(),
-            amount_owed: -19.50,
-        },
-    ];
-
-    Html(html! {
+    let restore_url = format!("/{nobt_id}/{expense_id}/restore");
+    let edit_url = format!("/{nobt_id}/{expense_id}/edit");
+    let latest_url = format!("/{nobt_id}/{}", nobt.latest_revision(bill).id);
+    let replaced = bill.replaced_by.is_some();
+    let debtees = &bill.debtees;
+    let paid_on = format::date(bill.date, Locale::current());
+    let added_on = format::date(bill.created_at.date(), Locale::current());
+    let total = bill.total;
+    let split_mode = bill.split.mode();
+    let mut history = storage.bill_activities(&nobt_id, &nobt.revisions(bill))?;
+    history.reverse();
+
+    let debtors = bill
+        .shares()
+        .into_iter()
+        .map(|(name, share)| DebtorItem {
+            name: name.to_owned(),
+            amount_owed: -share,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Html(html! {
         <App title=title>
             <Header>
                 <BackLink href=&nobt_url/>
                 <HeaderTitle title=name />
             </Header>
             <div class="bg-white p-4 flex flex-col gap-4">
-                <Section title="Debtee" subtitle="">
+                <Section title={t("Debtee")} subtitle="">
                     <List>
+                        {debtees
+                            .iter()
+                            .map(|debtee| async move { html! {
+                                <ListItem>
+                                    <Avatar name=&debtee.name />
+                                    <span class="flex-grow">{tf("{name} paid this bill.", &[("name", &debtee.name)])}</span>
+                                    <ThemedAmount value=debtee.amount />
+                                </ListItem>
+                            } })
+                            .collect_fragment_async().await}
+                        <ListItem>
+                            <ListItemIcon name=bill.category.icon()/>
+                            {tf("Spent on {category}.", &[("category", &category_label(&bill.category))])}
+                        </ListItem>
                         <ListItem>
-                            <Avatar name=&debtee_name />
-                            {format!("{debtee_name} paid this bill.")}
+                            <ListItemIcon name="event"/>
+                            {tf("Paid on {date}.", &[("date", &paid_on)])}
                         </ListItem>
                         <ListItem>
                             <ListItemIcon name="access_time"/>
-                            {format!("Added on {added_on}.")}
+                            {tf("Added on {date}.", &[("date", &added_on)])}
                         </ListItem>
                         <ListItem>
                             <ListItemIcon name="credit_card"/>
-                            {format!("The invoice total is {total}.")}
+                            {tf("The invoice total is {total}.", &[("total", &total)])}
+                        </ListItem>
+                        {match bill.exchange_rate {
+                            Some(rate) => html! {
+                                <ListItem>
+                                    <ListItemIcon name="currency_exchange"/>
+                                    {tf("Converted at {rate}, this makes {total}.", &[("rate", &rate), ("total", &bill.converted_total())])}
+                                </ListItem>
+                            },
+                            None => String::new(),
+                        }}
+                        <ListItem>
+                            <ListItemIcon name="balance"/>
+                            {split_description(split_mode)}
                         </ListItem>
                     </List>
                 </Section>
-                <Section title="Debtors" subtitle="">
+                <Section title={t("Debtors")} subtitle="">
                     <List>
                         {debtors
                             .iter()
@@ -714,95 +2677,353 @@ async fn expense(Path((nobt_id, expense_id)): Path<(String, u64)>) -> impl IntoR
                                 <ListItem>
                                     <Avatar name=&debtor.name />
                                     <span class="flex-grow">{debtor.name.as_str()}</span>
-                                    <ThemedAmount currency=currency value=debtor.amount_owed />
+                                    <ThemedAmount value=debtor.amount_owed />
                                 </ListItem>
                             } })
                             .collect_fragment_async().await}
                     </List>
                 </Section>
-                {
-                    if !deleted {
-                        html! {
-                           <Section title="Actions" subtitle="">
-                                <List>
-                                    <FormListItem href=delete_url confirm="Deleting a bill is permanent. Proceed?">
+                <Section title={t("History")} subtitle={t("Who added and changed this bill.")}>
+                    <ActivityList activities=&history />
+                </Section>
+                <Section title={t("Actions")} subtitle="">
+                    <List>
+                        {
+                            if replaced {
+                                html! {
+                                    <LinkListItem href=latest_url>
+                                        <ListItemIcon name="history"/>
+                                        <span class="flex-grow">{t("This bill has been edited, show the latest version")}</span>
+                                    </LinkListItem>
+                                }
+                            } else if !deleted {
+                                html! {
+                                    <LinkListItem href=edit_url>
+                                        <ListItemIcon name="edit"/>
+                                        <span class="flex-grow">{t("Edit this bill")}</span>
+                                    </LinkListItem>
+                                    <FormListItem href=delete_url confirm={t("Deleted bills no longer count towards the balances. Proceed?")}>
                                         <ListItemIcon name="delete"/>
-                                        "Delete this bill"
+                                        {t("Delete this bill")}
                                     </FormListItem>
-                                </List>
-                            </Section>
+                                }
+                            } else {
+                                html! {
+                                    <FormListItem href=restore_url confirm={t("Restored bills count towards the balances again. Proceed?")}>
+                                        <ListItemIcon name="restore_from_trash"/>
+                                        {t("Restore this bill")}
+                                    </FormListItem>
+                                }
+                            }
                         }
-                    } else {
-                        html! { }
-                    }
-                }
+                    </List>
+                </Section>
             </div>
         </App>
     })
+    .into_response())
 }
 
-/// Deletes an expense from a nobt.
+/// Opens the bill wizard pre-filled with an existing bill.
+async fn edit_expense(
+    State(storage): State<Arc<dyn Storage>>,
+    Path((nobt_id, expense_id)): Path<(String, u64)>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    let Some(bill) = nobt
+        .bill(expense_id)
+        .filter(|b| !b.deleted && b.replaced_by.is_none())
+    else {
+        return Err(AppError::BillNotFound);
+    };
+    let params = NewBillParameters::from_bill(bill);
+
+    new_bill(State(storage), Path(nobt_id), Form(params)).await
+}
+
+/// Saves the changes made in the bill wizard as a new revision of the bill.
 ///
-/// This returns a 303 See Other which is the appropriate way of sending the user somewhere else
-/// after a successful POST request.
+/// The previous revision is kept, so nothing is lost if a bill is edited by mistake.
+async fn revise_expense(
+    State(storage): State<Arc<dyn Storage>>,
+    Path((nobt_id, expense_id)): Path<(String, u64)>,
+    Actor(actor): Actor,
+    Form(params): Form<NewBillParameters>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let bill = match params.validate(&nobt) {
+        Ok(bill) => bill,
+        Err(errors) => {
+            return Ok((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Html(bill_wizard(&nobt, &params, &errors).await),
+            )
+                .into_response())
+        }
+    };
+
+    let Some(revision_id) = storage.revise_bill(&nobt_id, expense_id, bill, actor.as_deref())?
+    else {
+        return Err(AppError::BillNotFound);
+    };
+
+    Ok(SeeOther(format!("/{nobt_id}/{revision_id}")).into_response())
+}
+
+/// Deletes an expense from a nobt.
 ///
-/// See <https://www.rfc-editor.org/rfc/rfc9110.html#name-303-see-other>.
-async fn delete_expense(Path((nobt_id, _expense_id)): Path<(String, u64)>) -> impl IntoResponse {
-    Response::builder()
-        .status(303)
-        .header("Location", format!("/{nobt_id}"))
-        .body(String::new())
-        .unwrap()
-}
-
-async fn not_found() -> impl IntoResponse {
-    Html(html! {
-        <>
-            <!DOCTYPE html>
-            <Head title="Not found" />
-            <body hx-boost="true" class="bg-turquoise sm:bg-lightGrey h-screen">
-                <div class="sm:pt-10">
-                    <div class="bg-turquoise container mx-auto sm:shadow-lg sm:rounded-lg max-w-3xl">
-                        <Header>
-                            <h1 class="text-xl">"nobt.io"</h1>
-                        </Header>
-                        <div class="p-12 flex flex-col gap-4 items-center">
-                            // <div class="bg-cover h-80 w-2/3 bg-center bg-[url('/not_found.jpg')]">""</div>
-
-                            <h2 class="text-lg w-72 text-center text-white">"We looked everywhere but couldn't find this nobt."</h2>
-
-                            <a class="bg-white rounded-md px-4 py-2 shadow" href="/create">
-                                "Create a new nobt"
-                            </a>
-                        </div>
-                    </div>
-                </div>
-            </body>
-        </>
+/// Deleted expenses are kept in the nobt but no longer count towards the balances, they can be restored at any time.
+async fn delete_expense(
+    State(storage): State<Arc<dyn Storage>>,
+    Path((nobt_id, expense_id)): Path<(String, u64)>,
+    Actor(actor): Actor,
+) -> Result<Response, AppError> {
+    if !storage.set_bill_deleted(&nobt_id, expense_id, true, actor.as_deref())?
+        && !is_bill_deleted(&*storage, &nobt_id, expense_id, true)?
+    {
+        return Err(AppError::BillNotFound);
+    }
+
+    Ok(SeeOther(format!("/{nobt_id}")).into_response())
+}
+
+/// Restores a previously deleted expense.
+async fn restore_expense(
+    State(storage): State<Arc<dyn Storage>>,
+    Path((nobt_id, expense_id)): Path<(String, u64)>,
+    Actor(actor): Actor,
+) -> Result<Response, AppError> {
+    if !storage.set_bill_deleted(&nobt_id, expense_id, false, actor.as_deref())?
+        && !is_bill_deleted(&*storage, &nobt_id, expense_id, false)?
+    {
+        return Err(AppError::BillNotFound);
+    }
+
+    Ok(SeeOther(format!("/{nobt_id}/{expense_id}")).into_response())
+}
+
+/// Whether the latest revision of a bill is already deleted (or restored), e.g. because a form was submitted twice.
+fn is_bill_deleted(
+    storage: &dyn Storage,
+    nobt_id: &str,
+    bill_id: u64,
+    deleted: bool,
+) -> Result<bool> {
+    let nobt = storage.get_nobt(nobt_id)?;
+    let bill = nobt.as_ref().and_then(|nobt| nobt.bill(bill_id));
+
+    Ok(bill.is_some_and(|bill| bill.replaced_by.is_none() && bill.deleted == deleted))
+}
+
+/// Lists everything that happened in a nobt, newest first, and lets people tell who they are.
+async fn activity(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Actor(actor): Actor,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+    let mut activities = storage.activities(&nobt_id)?;
+    activities.reverse();
+
+    let title = nobt.title.as_str();
+    let nobt_url = format!("/{nobt_id}");
+    let names = nobt.participant_names().collect::<Vec<_>>();
+    let actor = actor.as_deref();
+
+    Ok(Html(html! {
+        <App title=title>
+            <Header>
+                <BackLink href=&nobt_url/>
+                <HeaderTitle title={t("Activity")} />
+            </Header>
+            <div class="bg-white p-4 flex flex-col gap-8">
+                <Section title={t("Who are you?")} subtitle={t("The changes you make in this nobt are recorded under this name, on this device.")}>
+                    <form method="post" action={format!("/{nobt_id}/activity/me")} class="w-full flex items-center gap-2">
+                        <select class="outline-none border-b py-2 bg-white flex-grow" name="name" aria-label={t("Who are you?")}>
+                            <option value="">{t("I'd rather not say")}</option>
+                            {names
+                                .iter()
+                                .map(|name| {
+                                    if actor == Some(*name) {
+                                        html! { <option value=name selected="true">{name}</option> }
+                                    } else {
+                                        html! { <option value=name>{name}</option> }
+                                    }
+                                })
+                                .collect_fragment()}
+                        </select>
+                        <button class="flex items-center hover:bg-hover gap-2 py-2 px-4 rounded-md shadow cursor-pointer">
+                            <Icon name="how_to_reg" />
+                            {t("Save")}
+                        </button>
+                    </form>
+                </Section>
+                <Section title={t("Activity")} subtitle={t("Everything that was added, edited, deleted or restored in this nobt.")}>
+                    <ActivityList activities=&activities />
+                </Section>
+            </div>
+        </App>
     })
+    .into_response())
+}
+
+#[derive(serde::Deserialize)]
+struct ActorForm {
+    name: String,
+}
+
+/// Remembers which participant is using this device, so that their changes show up under their name.
+///
+/// Nobts have no accounts, so this is only as trustworthy as the people sharing the nobt.
+async fn choose_actor(
+    State(storage): State<Arc<dyn Storage>>,
+    Path(nobt_id): Path<String>,
+    Form(form): Form<ActorForm>,
+) -> Result<Response, AppError> {
+    let Some(nobt) = storage.get_nobt(&nobt_id)? else {
+        return Err(AppError::NobtNotFound);
+    };
+
+    let cookie = if form.name.is_empty() {
+        activity::forget_actor_cookie(&nobt_id)
+    } else if nobt.has_participant(&form.name) {
+        format!(
+            "{ACTOR_COOKIE}={}; Path=/{nobt_id}; Max-Age=31536000; SameSite=Lax",
+            activity::encode_cookie_value(&form.name)
+        )
+    } else {
+        return Err(AppError::ParticipantNotFound);
+    };
+
+    Ok((
+        [(SET_COOKIE, cookie)],
+        SeeOther(format!("/{nobt_id}/activity")),
+    )
+        .into_response())
+}
+
+/// Describes an entry of the activity log, e.g. "Simon added 'Taxi zum Club' (€33.00)".
+fn activity_description(activity: &Activity) -> String {
+    let actor = activity.actor.as_deref().unwrap_or(t("Someone"));
+    let locale = Locale::current();
+
+    match &activity.event {
+        Event::BillAdded { name, total, .. } => tf(
+            "{actor} added '{bill}' ({amount})",
+            &[
+                ("actor", &actor),
+                ("bill", name),
+                ("amount", &format::amount(*total, locale)),
+            ],
+        ),
+        Event::BillEdited { name, total, .. } => tf(
+            "{actor} edited '{bill}' ({amount})",
+            &[
+                ("actor", &actor),
+                ("bill", name),
+                ("amount", &format::amount(*total, locale)),
+            ],
+        ),
+        Event::BillDeleted { name, total, .. } => tf(
+            "{actor} deleted '{bill}' ({amount})",
+            &[
+                ("actor", &actor),
+                ("bill", name),
+                ("amount", &format::amount(*total, locale)),
+            ],
+        ),
+        Event::BillRestored { name, total, .. } => tf(
+            "{actor} restored '{bill}' ({amount})",
+            &[
+                ("actor", &actor),
+                ("bill", name),
+                ("amount", &format::amount(*total, locale)),
+            ],
+        ),
+        Event::PaymentAdded { from, to, amount } => tf(
+            "{actor} recorded that {from} paid {to} ({amount})",
+            &[
+                ("actor", &actor),
+                ("from", from),
+                ("to", to),
+                ("amount", &format::amount(*amount, locale)),
+            ],
+        ),
+        Event::ParticipantAdded { name } => {
+            tf("{actor} added {name}", &[("actor", &actor), ("name", name)])
+        }
+        Event::ParticipantRenamed { name, new_name } => tf(
+            "{actor} renamed {name} to {new_name}",
+            &[("actor", &actor), ("name", name), ("new_name", new_name)],
+        ),
+        Event::ParticipantRemoved { name } => tf(
+            "{actor} removed {name}",
+            &[("actor", &actor), ("name", name)],
+        ),
+        Event::ParticipantsMerged { name, into } => tf(
+            "{actor} merged {name} into {into}",
+            &[("actor", &actor), ("name", name), ("into", into)],
+        ),
+    }
+}
+
+#[component]
+fn ActivityList(activities: &[Activity]) -> String {
+    if activities.is_empty() {
+        return html! {
+            <p class="text-darkGrey text-sm">{t("Nothing has happened here yet.")}</p>
+        };
+    }
+
+    html! {
+        <List>
+            {activities
+                .iter()
+                .map(|activity| html! {
+                    <ListItem>
+                        <ListItemIcon name=activity.event.icon()/>
+                        <span class="grow flex flex-col">
+                            <span>{activity_description(activity)}</span>
+                            <span class="text-darkGrey text-xs">{format::date_time(activity.created_at, Locale::current())}</span>
+                        </span>
+                    </ListItem>
+                })
+                .collect_fragment()}
+        </List>
+    }
 }
 
 struct ExpenseItem {
     description: String,
-    amount: f64,
-    url: String,
+    amount: Money,
+    icon: &'static str,
+    url: Option<String>,
     deleted: bool,
+    /// The day the bill was paid on or the payment was made.
+    date: Date,
 }
 
 struct DebtorItem {
     name: String,
-    amount_owed: f64,
+    amount_owed: Money,
 }
 
 struct BalanceItem {
     name: String,
-    amount: f64,
+    amount: Money,
     url: String,
 }
 
 struct DebtItem {
     name: String,
-    amount: f64,
+    amount: Money,
 }
 
 #[component]
@@ -898,41 +3119,25 @@ fn BackLink(href: String) -> String {
     }
 }
 
-/// Renders an amount in a given currency.
+/// Renders an amount in its currency.
 ///
 /// Negative amounts will appear red.
 #[component]
-fn ThemedAmount(currency: &str, value: f64) -> String {
-    if value == 0.0 {
-        html! { <Amount currency=currency value=value classes="text-darkGrey"/> }
-    } else if value < 0.0 {
-        html! { <Amount currency=currency value=value classes="text-red"/> }
+fn ThemedAmount(value: Money) -> String {
+    if value.is_zero() {
+        html! { <Amount value=value classes="text-darkGrey"/> }
+    } else if value.is_negative() {
+        html! { <Amount value=value classes="text-red"/> }
     } else {
-        html! { <Amount currency=currency value=value classes="text-green"/> }
+        html! { <Amount value=value classes="text-green"/> }
     }
 }
 
-/// Renders an amount in a given currency.
-///
-/// Negative amounts will appear red.
+/// Renders an amount in its currency.
 #[component]
-fn Amount(currency: &str, value: f64, classes: &'static str) -> String {
-    let formatted = format_amount(currency, value);
-
+fn Amount(value: Money, classes: &'static str) -> String {
     html! {
-        <span class={format!("text-sm {classes}")}>{formatted}</span>
-    }
-}
-
-fn format_amount(currency: &str, value: f64) -> String {
-    if value == 0.0 {
-        format!("{currency} 0.00")
-    } else if value < 0.0 {
-        let value = value.abs();
-
-        format!("-{currency} {value:.2}")
-    } else {
-        format!("{currency} {value:.2}")
+        <span class={format!("text-sm {classes}")}>{value.to_string()}</span>
     }
 }
 
@@ -958,12 +3163,34 @@ fn Avatar(name: String) -> String {
 #[component]
 fn Header(children: String) -> String {
     html! {
-        <header class="bg-grey text-white px-4 h-16 grid grid-cols-12 items-center">
+        <header class="relative bg-grey text-white px-4 h-16 grid grid-cols-12 items-center">
             {children}
+            <LanguageSwitcher classes="absolute top-1 right-2 text-xs" />
         </header>
     }
 }
 
+/// Links to switch the language of the UI, the current one is highlighted.
+#[component]
+fn LanguageSwitcher(classes: &'static str) -> String {
+    let current = Locale::current();
+
+    html! {
+        <nav class={format!("flex gap-1 uppercase {classes}")} aria-label={t("Language")}>
+            {Locale::ALL
+                .iter()
+                .map(|locale| {
+                    let opacity = if *locale == current { "" } else { "opacity-60" };
+
+                    html! {
+                        <a class=opacity href={format!("/language/{}", locale.code())} hreflang={locale.code()} hx-boost="false">{locale.code()}</a>
+                    }
+                })
+                .collect_fragment()}
+        </nav>
+    }
+}
+
 #[component]
 fn HeaderTitle(title: String) -> String {
     html! {
@@ -976,8 +3203,8 @@ fn FAB(nobt_id: String) -> String {
     html! {
         <div class="fixed bottom-6 right-6 transform-gpu space-y-4 text-right">
             <input id="fab-toggle" type="checkbox" class="hidden peer"/>
-            <FABLink href=format!("/{nobt_id}/payment") icon="credit_card" text="Pay someone" disabled=true index=1_u32/>
-            <FABLink href=format!("/{nobt_id}/bill") icon="receipt" text="Add a bill" disabled=false index=0_u32/>
+            <FABLink href=format!("/{nobt_id}/payment") icon="credit_card" text={t("Pay someone")} disabled=false index=1_u32/>
+            <FABLink href=format!("/{nobt_id}/bill") icon="receipt" text={t("Add a bill")} disabled=false index=0_u32/>
             <label for="fab-toggle" class="relative z-20 inline-block peer-checked:rotate-[225deg] duration-300 transition-transform cursor-pointer">
                 <FABIcon name="add" styles="bg-turquoise text-white"/>
             </label>
@@ -1064,15 +3291,12 @@ fn pick_bg_color(name: String) -> &'static str {
 }
 
 fn make_initials(name: String) -> String {
+    let initial = |word: &str| word.chars().next().map(String::from).unwrap_or_default();
+
     match name.split(' ').collect::<Vec<_>>().as_slice() {
         [] => String::new(), // TODO: Should never happen
-        [first] => match first.len() {
-            0 => unreachable!(),
-            1 => String::from(&name[..1]),
-            _ => String::from(&name[..2]),
-        },
-        [first, last] => format!("{}{}", &first[..1], &last[..1]),
-        [first, .., last] => format!("{}{}", &first[..1], &last[..1]),
+        [first] => first.chars().take(2).collect(),
+        [first, .., last] => format!("{}{}", initial(first), initial(last)),
     }
 }
 
@@ -1080,6 +3304,292 @@ fn make_initials(name: String) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn bill_form_accepts_split_that_adds_up() {
+        let params = bill_params("percentages", &[("Thomas", "60"), ("Simon", "40")]);
+
+        let bill = params.validate(&nobt()).unwrap();
+
+        assert_eq!(bill.debtors, vec!["Thomas", "Simon"]);
+        assert_eq!(bill.split, Split::Percentages(vec![6000, 4000]));
+    }
+
+    #[test]
+    fn bill_form_rejects_split_that_does_not_add_up() {
+        let split_error = |split_mode, values| {
+            bill_params(split_mode, values)
+                .validate(&nobt())
+                .unwrap_err()
+                .get("split")
+        };
+
+        assert_eq!(
+            split_error("percentages", &[("Thomas", "60"), ("Simon", "30")]),
+            Some("The percentages must add up to 100%.")
+        );
+        assert_eq!(
+            split_error("exact", &[("Thomas", "20"), ("Simon", "10")]),
+            Some("The amounts must add up to the total of the bill.")
+        );
+        assert_eq!(
+            split_error("shares", &[("Thomas", "2")]),
+            Some("Enter a value for everyone involved in the bill.")
+        );
+    }
+
+    #[test]
+    fn bill_form_accepts_several_debtees_that_add_up() {
+        let params = |amounts: [&str; 3]| {
+            let mut params = bill_params("equal", &[]);
+            params.debtee = None;
+            params.debtee_names = vec!["Thomas".to_owned(), "Simon".to_owned(), "Prada".to_owned()];
+            params.debtee_amounts = amounts.iter().map(|a| a.to_string()).collect();
+            params
+        };
+
+        assert_eq!(
+            params(["20", "13", ""]).validate(&nobt()).unwrap().debtees,
+            vec![
+                Debtee {
+                    name: "Thomas".to_owned(),
+                    amount: Money::new(2000, Currency::EUR)
+                },
+                Debtee {
+                    name: "Simon".to_owned(),
+                    amount: Money::new(1300, Currency::EUR)
+                },
+            ]
+        );
+        assert_eq!(
+            params(["20", "12", ""])
+                .validate(&nobt())
+                .unwrap_err()
+                .get("debtee"),
+            Some("The paid amounts must add up to the total of the bill.")
+        );
+    }
+
+    #[test]
+    fn bill_form_rejects_amounts_too_large_to_add_up() {
+        let mut params = bill_params("equal", &[]);
+        params.total = Some("10000000000.01".to_owned());
+
+        assert_eq!(
+            params.validate(&nobt()).unwrap_err().get("total"),
+            Some(AMOUNT_TOO_LARGE)
+        );
+    }
+
+    #[test]
+    fn bill_form_rejects_invalid_exact_amounts() {
+        let split_error = |value: &str| {
+            bill_params("exact", &[("Thomas", "20"), ("Simon", value)])
+                .validate(&nobt())
+                .unwrap_err()
+                .get("split")
+        };
+
+        assert_eq!(split_error("-13"), Some("Amounts must not be negative."));
+        assert_eq!(split_error("10000000000.01"), Some(AMOUNT_TOO_LARGE));
+        assert_eq!(
+            split_error("thirteen"),
+            Some("Enter a valid amount for everyone involved in the bill.")
+        );
+    }
+
+    #[test]
+    fn bill_form_reports_errors_of_all_fields() {
+        let mut params = bill_params("equal", &[]);
+        params.name = Some("  ".to_owned());
+        params.total = Some("-3".to_owned());
+        params.debtors = Some(HashSet::from(["Tho\nmas".to_owned()]));
+
+        let errors = params.validate(&nobt()).unwrap_err();
+
+        assert_eq!(errors.get("name"), Some("The bill needs a name."));
+        assert_eq!(
+            errors.get("total"),
+            Some("The total of a bill must be a positive amount.")
+        );
+        assert_eq!(
+            errors.get("debtors"),
+            Some("Names must not contain control characters.")
+        );
+    }
+
+    #[test]
+    fn bill_form_converts_other_currencies() {
+        let mut params = bill_params("exact", &[("Thomas", "20"), ("Simon", "13")]);
+        params.currency = Some("CHF".to_owned());
+
+        assert_eq!(
+            params.validate(&nobt()).unwrap_err().get("exchange_rate"),
+            Some("Enter the exchange rate of the currency the bill was paid in.")
+        );
+
+        params.exchange_rate = Some("1.04".to_owned());
+        params.rate_currency = Some("CHF".to_owned());
+        let bill = params.validate(&nobt()).unwrap();
+
+        assert_eq!(bill.total, Money::new(3300, Currency::CHF));
+        assert_eq!(
+            bill.exchange_rate,
+            Some(ExchangeRate::new(Currency::CHF, Currency::EUR, 1_040_000))
+        );
+        assert_eq!(
+            bill.split,
+            Split::Exact(vec![
+                Money::new(2000, Currency::CHF),
+                Money::new(1300, Currency::CHF)
+            ])
+        );
+    }
+
+    #[test]
+    fn exchange_rate_does_not_carry_over_to_another_currency() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        let mut params = bill_params("equal", &[]);
+        params.currency = Some("JPY".to_owned());
+        params.exchange_rate = Some("1.04".to_owned());
+        params.rate_currency = Some("CHF".to_owned());
+
+        assert_eq!(
+            params.validate(&nobt()).unwrap_err().get("exchange_rate"),
+            Some("Enter the exchange rate of the currency the bill was paid in.")
+        );
+
+        prefill_exchange_rate(&storage, &nobt(), &mut params).unwrap();
+        assert_eq!(params.exchange_rate, None);
+        assert_eq!(params.rate_currency.as_deref(), Some("JPY"));
+    }
+
+    #[test]
+    fn bills_are_paid_today_unless_another_day_is_entered() {
+        let mut params = bill_params("equal", &[]);
+        assert_eq!(params.validate(&nobt()).unwrap().date, today());
+
+        params.date = Some("2022-08-28".to_owned());
+        assert_eq!(
+            params.validate(&nobt()).unwrap().date,
+            Date::from_calendar_date(2022, time::Month::August, 28).unwrap()
+        );
+
+        params.date = Some(String::new());
+        assert_eq!(
+            params.validate(&nobt()).unwrap_err().get("date"),
+            Some("Enter the day the bill was paid on.")
+        );
+    }
+
+    #[test]
+    fn new_categories_are_used_instead_of_the_picked_one() {
+        let mut params = bill_params("equal", &[]);
+        assert_eq!(params.validate(&nobt()).unwrap().category, Category::Other);
+
+        params.category = Some("food".to_owned());
+        assert_eq!(params.validate(&nobt()).unwrap().category, Category::Food);
+
+        params.custom_category = Some(" Skiing ".to_owned());
+        assert_eq!(
+            params.validate(&nobt()).unwrap().category,
+            Category::Custom("Skiing".to_owned())
+        );
+    }
+
+    #[test]
+    fn new_categories_are_checked_like_names() {
+        let mut params = bill_params("equal", &[]);
+
+        params.custom_category = Some("Drinks".to_owned());
+        assert_eq!(params.validate(&nobt()).unwrap().category, Category::Drinks);
+
+        params.custom_category = Some("Ski\ting".to_owned());
+        assert_eq!(
+            params.validate(&nobt()).unwrap_err().get("category"),
+            Some("Names must not contain control characters.")
+        );
+    }
+
+    #[test]
+    fn bill_form_only_accepts_participants() {
+        let mut params = bill_params("equal", &[]);
+        params.debtee = Some("Prada".to_owned());
+        params.debtors = Some(HashSet::from(["Simon".to_owned(), "Benji".to_owned()]));
+
+        let errors = params.validate(&nobt()).unwrap_err();
+
+        assert_eq!(
+            errors.get("debtee"),
+            Some("Only participants of this nobt can be part of a bill.")
+        );
+        assert_eq!(
+            errors.get("debtors"),
+            Some("Only participants of this nobt can be part of a bill.")
+        );
+    }
+
+    #[test]
+    fn nobt_form_checks_names_of_participants() {
+        let form = |participants: &str| CreateNobtForm {
+            title: " Road Trip ".to_owned(),
+            currency: "EUR".to_owned(),
+            participants: participants.to_owned(),
+        };
+
+        let nobt = form("Thomas, Simon,, Thomas").validate().unwrap();
+        assert_eq!(nobt.title, "Road Trip");
+        assert_eq!(nobt.participants, vec!["Thomas", "Simon"]);
+
+        let errors = form("Thomas, Si\u{7}mon").validate().unwrap_err();
+        assert_eq!(
+            errors.get("participants"),
+            Some("Names must not contain control characters.")
+        );
+        let errors = form(&"x".repeat(41)).validate().unwrap_err();
+        assert_eq!(
+            errors.get("participants"),
+            Some("Names must not be longer than 40 characters.")
+        );
+    }
+
+    fn bill_params(split_mode: &str, values: &[(&str, &str)]) -> NewBillParameters {
+        NewBillParameters {
+            name: Some("Taxi zum Club".to_owned()),
+            total: Some("33.00".to_owned()),
+            debtee: Some("Simon".to_owned()),
+            debtee_names: Vec::new(),
+            debtee_amounts: Vec::new(),
+            debtors: Some(HashSet::from(["Simon".to_owned(), "Thomas".to_owned()])),
+            split_mode: Some(split_mode.to_owned()),
+            split_names: values.iter().map(|(name, _)| name.to_string()).collect(),
+            split_values: values.iter().map(|(_, value)| value.to_string()).collect(),
+            revision_of: None,
+            new_participant: None,
+            currency: None,
+            exchange_rate: None,
+            rate_currency: None,
+            date: None,
+            category: None,
+            custom_category: None,
+        }
+    }
+
+    fn nobt() -> Nobt {
+        Nobt {
+            id: "abc".to_owned(),
+            title: "Swedish Shenanigans".to_owned(),
+            currency: Currency::EUR,
+            participants: ["Thomas", "Simon"]
+                .into_iter()
+                .map(|name| nobt::Participant {
+                    name: name.to_owned(),
+                })
+                .collect(),
+            bills: Vec::new(),
+            payments: Vec::new(),
+        }
+    }
+
     #[test]
     fn make_initials_only_firstname() {
         assert_eq!(make_initials("Thomas"), "Th");
@@ -1099,4 +3609,11 @@ mod tests {
     fn make_initials_middle_name() {
         assert_eq!(make_initials("Bar Foo Baz"), "BB");
     }
+
+    #[test]
+    fn make_initials_non_ascii_name() {
+        assert_eq!(make_initials("Özil"), "Öz");
+        assert_eq!(make_initials("李"), "李");
+        assert_eq!(make_initials("Jürgen Çelik"), "JÇ");
+    }
 }
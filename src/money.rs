@@ -0,0 +1,564 @@
+use crate::format::{self, Locale};
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// The currencies a nobt can be kept in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(clippy::upper_case_acronyms)] // We name currencies by their ISO 4217 code.
+pub enum Currency {
+    EUR,
+    USD,
+    GBP,
+    CHF,
+    SEK,
+    NOK,
+    DKK,
+    ISK,
+    PLN,
+    CZK,
+    HUF,
+    CAD,
+    AUD,
+    JPY,
+}
+
+impl Currency {
+    pub const ALL: &'static [Currency] = &[
+        Currency::EUR,
+        Currency::USD,
+        Currency::GBP,
+        Currency::CHF,
+        Currency::SEK,
+        Currency::NOK,
+        Currency::DKK,
+        Currency::ISK,
+        Currency::PLN,
+        Currency::CZK,
+        Currency::HUF,
+        Currency::CAD,
+        Currency::AUD,
+        Currency::JPY,
+    ];
+
+    /// The ISO 4217 code of this currency.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::EUR => "EUR",
+            Currency::USD => "USD",
+            Currency::GBP => "GBP",
+            Currency::CHF => "CHF",
+            Currency::SEK => "SEK",
+            Currency::NOK => "NOK",
+            Currency::DKK => "DKK",
+            Currency::ISK => "ISK",
+            Currency::PLN => "PLN",
+            Currency::CZK => "CZK",
+            Currency::HUF => "HUF",
+            Currency::CAD => "CAD",
+            Currency::AUD => "AUD",
+            Currency::JPY => "JPY",
+        }
+    }
+
+    /// The symbol amounts of this currency are displayed with, e.g. `€` or `CHF`.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::EUR => "€",
+            Currency::USD => "$",
+            Currency::GBP => "£",
+            Currency::CHF => "CHF",
+            Currency::SEK | Currency::NOK | Currency::DKK | Currency::ISK => "kr",
+            Currency::PLN => "zł",
+            Currency::CZK => "Kč",
+            Currency::HUF => "Ft",
+            Currency::CAD => "CA$",
+            Currency::AUD => "A$",
+            Currency::JPY => "¥",
+        }
+    }
+
+    /// How many digits come after the decimal separator, i.e. how many minor units make up one major unit.
+    ///
+    /// These are the minor units defined by ISO 4217, e.g. yen have none.
+    pub fn decimal_places(&self) -> u32 {
+        match self {
+            Currency::ISK | Currency::JPY => 0,
+            _ => 2,
+        }
+    }
+
+    /// The smallest amount that can be entered in forms, e.g. `0.01` for euros or `1` for yen.
+    pub fn step(&self) -> String {
+        Money::new(1, *self).to_decimal_string()
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Currency::ALL
+            .iter()
+            .find(|c| c.code() == s)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("unsupported currency '{s}'"))
+    }
+}
+
+/// An exact amount of money in a particular currency.
+///
+/// Amounts are stored as an integer number of minor units (e.g. cents) so summing them never loses precision.
+/// Combining amounts of different currencies is a bug and panics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Money {
+    minor_units: i64,
+    currency: Currency,
+}
+
+impl Money {
+    /// The largest amount accepted from users, in minor units, e.g. ten billion euros.
+    ///
+    /// This leaves enough headroom that summing the bills and payments of a nobt can never overflow.
+    pub const MAX_MINOR_UNITS: i64 = 1_000_000_000_000;
+
+    pub fn new(minor_units: i64, currency: Currency) -> Self {
+        Self {
+            minor_units,
+            currency,
+        }
+    }
+
+    pub fn zero(currency: Currency) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// Parses user input like `12`, `12.3` or `12,34` into an amount of the given currency.
+    ///
+    /// Returns `None` if the input is not a number, has more decimal places than the currency allows or is larger than
+    /// [`Money::MAX_MINOR_UNITS`].
+    pub fn parse(input: &str, currency: Currency) -> Option<Self> {
+        parse_decimal(input, currency.decimal_places())
+            .filter(|minor_units| minor_units.abs() <= Self::MAX_MINOR_UNITS)
+            .map(|minor_units| Self::new(minor_units, currency))
+    }
+
+    /// Whether the input is a well-formed amount that [`Money::parse`] only rejects because it is too large.
+    pub fn is_too_large(input: &str, currency: Currency) -> bool {
+        parse_decimal(input, currency.decimal_places())
+            .is_some_and(|minor_units| minor_units.abs() > Self::MAX_MINOR_UNITS)
+    }
+
+    pub fn minor_units(&self) -> i64 {
+        self.minor_units
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor_units == 0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.minor_units < 0
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.minor_units > 0
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(self.minor_units.abs(), self.currency)
+    }
+
+    /// Splits this amount proportionally to the given weights into amounts that sum up to exactly this amount.
+    ///
+    /// Every part first gets its proportional amount rounded down. The leftover minor units go to the parts that
+    /// lost the most by rounding down. Ties are broken in order, starting at the part with index `start`.
+    ///
+    /// Panics if all weights are zero.
+    pub fn allocate(self, weights: &[u64], start: usize) -> Vec<Money> {
+        if weights.is_empty() {
+            return Vec::new();
+        }
+
+        let total = i128::from(self.minor_units);
+        let sum = weights.iter().map(|w| i128::from(*w)).sum::<i128>();
+        assert!(sum > 0, "cannot allocate an amount to zero weights");
+
+        let mut parts = weights
+            .iter()
+            .map(|w| (total * i128::from(*w)).div_euclid(sum) as i64)
+            .collect::<Vec<_>>();
+        let remainders = weights
+            .iter()
+            .map(|w| (total * i128::from(*w)).rem_euclid(sum))
+            .collect::<Vec<_>>();
+        let leftover = (self.minor_units - parts.iter().sum::<i64>()) as usize;
+
+        let mut order = (0..parts.len())
+            .map(|i| (start + i) % parts.len())
+            .collect::<Vec<_>>();
+        // Stable sort to keep the order of parts with the same remainder.
+        order.sort_by_key(|i| std::cmp::Reverse(remainders[*i]));
+        for i in order.into_iter().take(leftover) {
+            parts[i] += 1;
+        }
+
+        parts
+            .into_iter()
+            .map(|minor_units| Money::new(minor_units, self.currency))
+            .collect()
+    }
+
+    /// Formats the amount without currency, the way it is entered in forms, e.g. `12.30`.
+    pub fn to_decimal_string(self) -> String {
+        let decimal_places = self.currency.decimal_places();
+        let sign = if self.is_negative() { "-" } else { "" };
+        let factor = 10_i64.pow(decimal_places);
+        let major = self.minor_units.abs() / factor;
+        let minor = self.minor_units.abs() % factor;
+
+        if decimal_places == 0 {
+            format!("{sign}{major}")
+        } else {
+            format!(
+                "{sign}{major}.{minor:0width$}",
+                width = decimal_places as usize
+            )
+        }
+    }
+
+    fn assert_same_currency(&self, other: &Self) {
+        assert_eq!(
+            self.currency, other.currency,
+            "cannot combine amounts of different currencies"
+        );
+    }
+}
+
+/// What one unit of a currency is worth in another currency, e.g. `1 CHF = 1.0412 EUR`.
+///
+/// Rates are exact decimals with up to six decimal places, kept as an integer number of millionths.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExchangeRate {
+    from: Currency,
+    to: Currency,
+    millionths: i64,
+}
+
+impl ExchangeRate {
+    const DECIMAL_PLACES: u32 = 6;
+
+    pub fn new(from: Currency, to: Currency, millionths: i64) -> Self {
+        Self {
+            from,
+            to,
+            millionths,
+        }
+    }
+
+    /// Parses a rate entered by a user, e.g. `1.0412` or `0,96`.
+    ///
+    /// Returns `None` if the input is not a positive number with at most six decimal places.
+    pub fn parse(input: &str, from: Currency, to: Currency) -> Option<Self> {
+        parse_decimal(input, Self::DECIMAL_PLACES)
+            .filter(|millionths| *millionths > 0)
+            .map(|millionths| Self::new(from, to, millionths))
+    }
+
+    pub fn from(&self) -> Currency {
+        self.from
+    }
+
+    pub fn millionths(&self) -> i64 {
+        self.millionths
+    }
+
+    /// Converts an amount of the source currency, rounding half away from zero to a minor unit of the target currency.
+    ///
+    /// Panics if the amount is not in the source currency of this rate.
+    pub fn convert(&self, amount: Money) -> Money {
+        Money::new(self.convert_minor_units(amount) as i64, self.to)
+    }
+
+    /// Converts an amount like [`ExchangeRate::convert`], unless the result is larger than [`Money::MAX_MINOR_UNITS`].
+    pub fn checked_convert(&self, amount: Money) -> Option<Money> {
+        i64::try_from(self.convert_minor_units(amount))
+            .ok()
+            .filter(|minor_units| minor_units.abs() <= Money::MAX_MINOR_UNITS)
+            .map(|minor_units| Money::new(minor_units, self.to))
+    }
+
+    fn convert_minor_units(&self, amount: Money) -> i128 {
+        assert_eq!(
+            amount.currency(),
+            self.from,
+            "cannot convert an amount of another currency"
+        );
+
+        let numerator = i128::from(amount.minor_units())
+            * i128::from(self.millionths)
+            * 10_i128.pow(self.to.decimal_places());
+        let denominator = 10_i128.pow(Self::DECIMAL_PLACES + self.from.decimal_places());
+
+        (numerator.abs() + denominator / 2) / denominator * numerator.signum()
+    }
+
+    /// Formats the rate the way it is entered in forms, without trailing zeros, e.g. `1.0412`.
+    pub fn to_decimal_string(self) -> String {
+        let factor = 10_i64.pow(Self::DECIMAL_PLACES);
+        let decimals = format!(
+            "{:0width$}",
+            self.millionths % factor,
+            width = Self::DECIMAL_PLACES as usize
+        );
+        let decimals = decimals.trim_end_matches('0');
+
+        if decimals.is_empty() {
+            format!("{}", self.millionths / factor)
+        } else {
+            format!("{}.{decimals}", self.millionths / factor)
+        }
+    }
+}
+
+/// Formats the rate for display, e.g. `1 CHF = 1.0412 EUR`.
+impl fmt::Display for ExchangeRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "1 {} = {} {}",
+            self.from,
+            self.to_decimal_string(),
+            self.to
+        )
+    }
+}
+
+/// Parses a decimal number like `12`, `12.3` or `12,34` into an integer scaled by `10^decimal_places`.
+///
+/// Returns `None` if the input is not a number or has more than `decimal_places` decimal places.
+pub fn parse_decimal(input: &str, decimal_places: u32) -> Option<i64> {
+    let input = input.trim();
+    let (negative, input) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    let (major, minor) = match input.split_once(['.', ',']) {
+        Some((major, minor)) => (major, minor),
+        None => (input, ""),
+    };
+
+    let is_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+    if major.is_empty()
+        || !is_digits(major)
+        || !is_digits(minor)
+        || minor.len() > decimal_places as usize
+    {
+        return None;
+    }
+
+    let major = major.parse::<i64>().ok()?;
+    let minor = format!("{minor:0<width$}", width = decimal_places as usize);
+    let minor = if minor.is_empty() {
+        0
+    } else {
+        minor.parse::<i64>().ok()?
+    };
+
+    let value = major
+        .checked_mul(10_i64.pow(decimal_places))?
+        .checked_add(minor)?;
+
+    Some(if negative { -value } else { value })
+}
+
+/// Formats the amount with the symbol of its currency for the locale of the current request, see [`format::amount`].
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format::amount(*self, Locale::current()))
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.assert_same_currency(&rhs);
+
+        Money::new(self.minor_units + rhs.minor_units, self.currency)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.assert_same_currency(&rhs);
+
+        Money::new(self.minor_units - rhs.minor_units, self.currency)
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+
+    fn neg(self) -> Self::Output {
+        Money::new(-self.minor_units, self.currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_user_input() {
+        let parse = |input| Money::parse(input, Currency::EUR).map(|m| m.minor_units());
+
+        assert_eq!(parse("12"), Some(1200));
+        assert_eq!(parse("12.3"), Some(1230));
+        assert_eq!(parse("12.34"), Some(1234));
+        assert_eq!(parse("12,34"), Some(1234));
+        assert_eq!(parse(" 0.07 "), Some(7));
+        assert_eq!(parse("-1.50"), Some(-150));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        let parse = |input| Money::parse(input, Currency::EUR);
+
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("abc"), None);
+        assert_eq!(parse(".5"), None);
+        assert_eq!(parse("12.345"), None);
+        assert_eq!(parse("1e3"), None);
+        assert_eq!(parse("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn amounts_are_capped_so_sums_cannot_overflow() {
+        assert!(Money::parse("10000000000", Currency::EUR).is_some());
+        assert_eq!(Money::parse("10000000000.01", Currency::EUR), None);
+        assert!(Money::is_too_large("10000000000.01", Currency::EUR));
+        assert!(!Money::is_too_large("abc", Currency::EUR));
+
+        let rate = ExchangeRate::new(Currency::JPY, Currency::EUR, 1_000_000);
+        let yen = Money::new(Money::MAX_MINOR_UNITS, Currency::JPY);
+        assert_eq!(rate.checked_convert(yen), None);
+        assert_eq!(
+            rate.checked_convert(Money::new(1000, Currency::JPY)),
+            Some(Money::new(100_000, Currency::EUR))
+        );
+    }
+
+    #[test]
+    fn formats_with_currency() {
+        assert_eq!(Money::new(1234, Currency::EUR).to_string(), "€12.34");
+        assert_eq!(Money::new(-5, Currency::EUR).to_string(), "-€0.05");
+        assert_eq!(Money::zero(Currency::CHF).to_string(), "CHF 0.00");
+        assert_eq!(Money::new(1500, Currency::JPY).to_string(), "¥1,500");
+    }
+
+    #[test]
+    fn decimal_places_follow_the_currency() {
+        assert_eq!(
+            Money::parse("1500", Currency::JPY).map(|m| m.minor_units()),
+            Some(1500)
+        );
+        assert_eq!(Money::parse("15.5", Currency::JPY), None);
+        assert_eq!(Currency::JPY.step(), "1");
+        assert_eq!(Currency::GBP.step(), "0.01");
+    }
+
+    #[test]
+    fn equal_allocation_gives_leftover_to_first_parts() {
+        let allocate = |minor_units, parts| {
+            Money::new(minor_units, Currency::EUR)
+                .allocate(&vec![1; parts], 0)
+                .into_iter()
+                .map(|m| m.minor_units())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(allocate(1000, 3), vec![334, 333, 333]);
+        assert_eq!(allocate(1001, 3), vec![334, 334, 333]);
+        assert_eq!(allocate(900, 3), vec![300, 300, 300]);
+        assert_eq!(allocate(2, 3), vec![1, 1, 0]);
+        assert_eq!(allocate(-1000, 3), vec![-333, -333, -334]);
+        assert_eq!(allocate(1000, 0), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn allocate_is_proportional_to_weights() {
+        let allocate = |minor_units, weights: &[u64], start| {
+            Money::new(minor_units, Currency::EUR)
+                .allocate(weights, start)
+                .into_iter()
+                .map(|m| m.minor_units())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(allocate(1000, &[2, 1, 1], 0), vec![500, 250, 250]);
+        assert_eq!(allocate(1000, &[1, 1, 1], 1), vec![333, 334, 333]);
+        assert_eq!(allocate(1000, &[5000, 3333, 1667], 0), vec![500, 333, 167]);
+        assert_eq!(allocate(100, &[1, 2], 0), vec![33, 67]);
+        assert_eq!(allocate(1000, &[], 0), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn exchange_rates_convert_between_decimal_places() {
+        let rate = ExchangeRate::parse("1.0412", Currency::CHF, Currency::EUR).unwrap();
+        assert_eq!(
+            rate.convert(Money::new(10000, Currency::CHF)),
+            Money::new(10412, Currency::EUR)
+        );
+        assert_eq!(
+            rate.convert(Money::new(-1, Currency::CHF)),
+            Money::new(-1, Currency::EUR)
+        );
+        assert_eq!(rate.to_string(), "1 CHF = 1.0412 EUR");
+
+        let rate = ExchangeRate::parse("0.006125", Currency::JPY, Currency::EUR).unwrap();
+        assert_eq!(
+            rate.convert(Money::new(2000, Currency::JPY)),
+            Money::new(1225, Currency::EUR)
+        );
+        assert_eq!(
+            ExchangeRate::new(Currency::EUR, Currency::JPY, 163_000_000).to_decimal_string(),
+            "163"
+        );
+
+        assert_eq!(ExchangeRate::parse("0", Currency::CHF, Currency::EUR), None);
+    }
+
+    #[test]
+    fn sums_are_exact() {
+        let ten_cents = Money::new(10, Currency::EUR);
+
+        let sum = (0..1000).fold(Money::zero(Currency::EUR), |sum, _| sum + ten_cents);
+
+        assert_eq!(sum, Money::new(10000, Currency::EUR));
+    }
+}
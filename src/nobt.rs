@@ -0,0 +1,787 @@
+use crate::money::{Currency, ExchangeRate, Money};
+use rand::Rng;
+use std::collections::HashMap;
+use std::str::FromStr;
+use time::{Date, OffsetDateTime};
+
+/// A nobt is a group of people sharing bills, e.g. for a trip or a party.
+#[derive(Clone, Debug)]
+pub struct Nobt {
+    pub id: String,
+    pub title: String,
+    pub currency: Currency,
+    pub participants: Vec<Participant>,
+    pub bills: Vec<Bill>,
+    pub payments: Vec<Payment>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Participant {
+    pub name: String,
+}
+
+/// A bill that was paid by one or more persons (the debtees) on behalf of several others (the debtors).
+///
+/// The total, the amounts of the debtees and exact splits are in the currency the bill was paid in.
+#[derive(Clone, Debug)]
+pub struct Bill {
+    pub id: u64,
+    pub name: String,
+    pub total: Money,
+    pub debtees: Vec<Debtee>,
+    pub debtors: Vec<String>,
+    pub split: Split,
+    /// Converts the amounts into the currency of the nobt, if the bill was paid in another currency.
+    pub exchange_rate: Option<ExchangeRate>,
+    /// The day the bill was paid on.
+    pub date: Date,
+    pub category: Category,
+    pub deleted: bool,
+    /// Bills are never changed. Editing a bill creates a new revision of it, this is the ID of that revision.
+    pub replaced_by: Option<u64>,
+    /// When the bill was added to the nobt. Revisions keep the time of the bill they replace.
+    pub created_at: OffsetDateTime,
+}
+
+/// Someone who paid (part of) a bill.
+///
+/// The amounts of all debtees of a bill add up to its total.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Debtee {
+    pub name: String,
+    pub amount: Money,
+}
+
+/// How the total of a bill is divided between its debtors.
+///
+/// The values are in the same order as the debtors of the bill.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Split {
+    /// Everyone pays the same.
+    Equal,
+    /// Everyone pays proportionally to their number of shares, e.g. two shares for whoever had the big room.
+    Shares(Vec<u64>),
+    /// Everyone pays a percentage of the total, in hundredths of a percent that add up to 10000.
+    Percentages(Vec<u64>),
+    /// Everyone pays an exact amount, the amounts add up to the total.
+    Exact(Vec<Money>),
+}
+
+/// What a bill was spent on.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Category {
+    Food,
+    Transport,
+    Accommodation,
+    Drinks,
+    Activities,
+    Other,
+    /// A category named by the participants of a nobt.
+    Custom(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitMode {
+    Equal,
+    Shares,
+    Percentages,
+    Exact,
+}
+
+/// Money that one participant transferred to another, usually to settle their debts.
+#[derive(Clone, Debug)]
+pub struct Payment {
+    pub from: String,
+    pub to: String,
+    pub amount: Money,
+    pub note: Option<String>,
+    pub created_at: OffsetDateTime,
+}
+
+/// A nobt that has not been stored yet.
+#[derive(Clone, Debug)]
+pub struct NewNobt {
+    pub title: String,
+    pub currency: Currency,
+    pub participants: Vec<String>,
+}
+
+/// A bill that has not been stored yet.
+#[derive(Clone, Debug)]
+pub struct NewBill {
+    pub name: String,
+    pub total: Money,
+    pub debtees: Vec<Debtee>,
+    pub debtors: Vec<String>,
+    pub split: Split,
+    pub exchange_rate: Option<ExchangeRate>,
+    pub date: Date,
+    pub category: Category,
+}
+
+/// A payment that has not been stored yet.
+#[derive(Clone, Debug)]
+pub struct NewPayment {
+    pub from: String,
+    pub to: String,
+    pub amount: Money,
+    pub note: Option<String>,
+}
+
+/// Joins names for display, e.g. `Thomas, Simon and Prada`.
+pub fn join_names(names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.to_string(),
+        [rest @ .., last] => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
+/// The characters of nobt IDs: Crockford's Base32, which leaves out letters that are easily mistaken for others.
+const ID_ALPHABET: &[u8; 32] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+/// The length of a nobt ID, giving 80 bits of entropy.
+///
+/// The ID of a nobt is all it takes to access it, so it must not be guessable.
+const ID_LENGTH: usize = 16;
+
+/// The number of characters between the dashes of the human-friendly form of an ID.
+const ID_GROUP_LENGTH: usize = 4;
+
+/// Generates a fresh ID for a nobt.
+pub fn new_id() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..ID_LENGTH)
+        .map(|_| ID_ALPHABET[rng.gen_range(0..ID_ALPHABET.len())] as char)
+        .collect()
+}
+
+/// Parses the ID of a nobt, returning it in its canonical form.
+///
+/// Besides the canonical form, this accepts what people type after having an ID read out to them: the human-friendly
+/// form with dashes, uppercase letters and `i`, `l` and `o` in place of `1` and `0`. Returns `None` for anything that
+/// can't be the ID of a nobt.
+pub fn parse_id(input: &str) -> Option<String> {
+    // Nobts created before IDs used Base32 have 12 case-sensitive letters and digits.
+    if input.len() == 12 && input.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return Some(input.to_owned());
+    }
+
+    let id = input
+        .chars()
+        .filter(|c| *c != '-')
+        .map(|c| match c.to_ascii_lowercase() {
+            'i' | 'l' => '1',
+            'o' => '0',
+            c => c,
+        })
+        .collect::<String>();
+
+    (id.len() == ID_LENGTH && id.bytes().all(|b| ID_ALPHABET.contains(&b))).then_some(id)
+}
+
+/// The human-friendly form of a nobt ID which is easy to read aloud, e.g. `k3f9-x2mq-7hta-c4vw`.
+pub fn display_id(id: &str) -> String {
+    if id.len() != ID_LENGTH {
+        return id.to_owned();
+    }
+
+    id.as_bytes()
+        .chunks(ID_GROUP_LENGTH)
+        .map(|group| String::from_utf8_lossy(group))
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+impl Nobt {
+    pub fn bill(&self, id: u64) -> Option<&Bill> {
+        self.bills.iter().find(|b| b.id == id)
+    }
+
+    pub fn has_participant(&self, name: &str) -> bool {
+        self.participants.iter().any(|p| p.name == name)
+    }
+
+    /// Whether a participant, or any bill or payment, uses the name.
+    ///
+    /// Removed participants keep their name in old bills and payments, so it can't be given to someone else.
+    pub fn is_name_used(&self, name: &str) -> bool {
+        self.has_participant(name)
+            || self.bills.iter().any(|b| {
+                b.debtees.iter().any(|d| d.name == name) || b.debtors.iter().any(|d| d == name)
+            })
+            || self.payments.iter().any(|p| p.from == name || p.to == name)
+    }
+
+    pub fn participant_names(&self) -> impl Iterator<Item = &str> {
+        self.participants.iter().map(|p| p.name.as_str())
+    }
+
+    /// The latest revision of every bill of this nobt, including deleted bills.
+    pub fn current_bills(&self) -> impl DoubleEndedIterator<Item = &Bill> {
+        self.bills.iter().filter(|b| b.replaced_by.is_none())
+    }
+
+    /// The bills of this nobt which have neither been deleted nor replaced by a newer revision.
+    pub fn active_bills(&self) -> impl Iterator<Item = &Bill> {
+        self.current_bills().filter(|b| !b.deleted)
+    }
+
+    /// Follows the revisions of a bill up to the latest one.
+    pub fn latest_revision<'a>(&'a self, mut bill: &'a Bill) -> &'a Bill {
+        while let Some(next) = bill.replaced_by.and_then(|id| self.bill(id)) {
+            bill = next;
+        }
+
+        bill
+    }
+
+    /// The IDs of all revisions of a bill, from the first one up to the latest one.
+    pub fn revisions(&self, bill: &Bill) -> Vec<u64> {
+        let next = self
+            .bills
+            .iter()
+            .filter_map(|b| Some((b.id, b.replaced_by?)))
+            .collect::<HashMap<_, _>>();
+        let previous = next
+            .iter()
+            .map(|(id, next)| (*next, *id))
+            .collect::<HashMap<_, _>>();
+
+        let mut first = bill.id;
+        while let Some(id) = previous.get(&first) {
+            first = *id;
+        }
+        let mut revisions = vec![first];
+        while let Some(id) = revisions.last().and_then(|id| next.get(id)) {
+            revisions.push(*id);
+        }
+
+        revisions
+    }
+
+    /// Folds the participant `name` into `into`, e.g. when the same person was added twice under different names.
+    ///
+    /// `into` takes over all bills and payments of `name`. Payments between the two are dropped, as they would be
+    /// payments to oneself.
+    pub fn merge_participants(&mut self, name: &str, into: &str) {
+        self.participants.retain(|p| p.name != name);
+
+        for bill in &mut self.bills {
+            bill.merge_participant(name, into);
+        }
+
+        self.payments.retain(|p| !p.is_between(name, into));
+        for payment in &mut self.payments {
+            for person in [&mut payment.from, &mut payment.to] {
+                if person == name {
+                    *person = into.to_owned();
+                }
+            }
+        }
+    }
+
+    /// The sum of all bills in this nobt.
+    pub fn total(&self) -> Money {
+        self.active_bills()
+            .fold(Money::zero(self.currency), |sum, b| {
+                sum + b.converted_total()
+            })
+    }
+
+    /// How much was spent on each category, the biggest expenses first.
+    pub fn category_totals(&self) -> Vec<(&Category, Money)> {
+        let mut totals = Vec::<(&Category, Money)>::new();
+
+        for bill in self.active_bills() {
+            match totals
+                .iter_mut()
+                .find(|(category, _)| **category == bill.category)
+            {
+                Some((_, total)) => *total += bill.converted_total(),
+                None => totals.push((&bill.category, bill.converted_total())),
+            }
+        }
+        totals.sort_by_key(|(_, total)| std::cmp::Reverse(total.minor_units()));
+
+        totals
+    }
+
+    /// The categories the participants of this nobt made up, in the order they were first used.
+    pub fn custom_categories(&self) -> Vec<&str> {
+        let mut categories = Vec::new();
+
+        for bill in self.current_bills() {
+            if let Category::Custom(name) = &bill.category {
+                if !categories.contains(&name.as_str()) {
+                    categories.push(name.as_str());
+                }
+            }
+        }
+
+        categories
+    }
+}
+
+impl Bill {
+    /// The total of this bill in the currency of its nobt.
+    pub fn converted_total(&self) -> Money {
+        match self.exchange_rate {
+            Some(rate) => rate.convert(self.total),
+            None => self.total,
+        }
+    }
+
+    /// What each debtee paid in the currency of the nobt, in the order of the debtees.
+    ///
+    /// The converted total is divided in proportion to the paid amounts, so the parts always sum up to it exactly.
+    pub fn paid_amounts(&self) -> Vec<(&str, Money)> {
+        let amounts = match self.exchange_rate {
+            Some(_) => self
+                .converted_total()
+                .allocate(&minor_units(self.debtees.iter().map(|d| d.amount)), 0),
+            None => self.debtees.iter().map(|d| d.amount).collect(),
+        };
+
+        self.debtees
+            .iter()
+            .map(|d| d.name.as_str())
+            .zip(amounts)
+            .collect()
+    }
+
+    /// What each debtor owes for this bill in the currency of the nobt, in the order of the debtors.
+    ///
+    /// Unless the bill is split into exact amounts, minor units that cannot be split evenly are handed out one by one,
+    /// starting at a debtor that rotates with the bill's ID. This way, the same person doesn't always pay the extra cent
+    /// but the result is still deterministic: The shares always sum up to the total and don't change between page views.
+    pub fn shares(&self) -> Vec<(&str, Money)> {
+        let start = match self.debtors.len() {
+            0 => 0,
+            len => self.id as usize % len,
+        };
+        let total = self.converted_total();
+        let shares = match &self.split {
+            Split::Equal => total.allocate(&vec![1; self.debtors.len()], start),
+            Split::Shares(weights) | Split::Percentages(weights) => total.allocate(weights, start),
+            Split::Exact(amounts) if self.exchange_rate.is_some() => {
+                total.allocate(&minor_units(amounts.iter().copied()), start)
+            }
+            Split::Exact(amounts) => amounts.clone(),
+        };
+
+        self.debtors
+            .iter()
+            .map(|d| d.as_str())
+            .zip(shares)
+            .collect()
+    }
+
+    pub fn is_debtor(&self, name: &str) -> bool {
+        self.debtors.iter().any(|d| d == name)
+    }
+
+    pub fn is_debtee(&self, name: &str) -> bool {
+        self.debtees.iter().any(|d| d.name == name)
+    }
+
+    /// Replaces `name` by `into` as debtee and debtor of this bill, returning whether `name` was involved at all.
+    ///
+    /// If both are involved, their parts are added up, so that `into` pays and owes as much as both did before.
+    pub fn merge_participant(&mut self, name: &str, into: &str) -> bool {
+        let mut is_involved = false;
+
+        if let Some(index) = self.debtees.iter().position(|d| d.name == name) {
+            is_involved = true;
+            match self.debtees.iter().position(|d| d.name == into) {
+                Some(into_index) => {
+                    let amount = self.debtees[index].amount;
+                    self.debtees[into_index].amount += amount;
+                    self.debtees.remove(index);
+                }
+                None => self.debtees[index].name = into.to_owned(),
+            }
+        }
+
+        if let Some(index) = self.debtors.iter().position(|d| d == name) {
+            is_involved = true;
+            match self.debtors.iter().position(|d| d == into) {
+                Some(into_index) => {
+                    // An equal split is one share each, `into` gets the share of `name` on top.
+                    if self.split == Split::Equal {
+                        self.split = Split::Shares(vec![1; self.debtors.len()]);
+                    }
+                    match &mut self.split {
+                        Split::Equal => {}
+                        Split::Shares(weights) | Split::Percentages(weights) => {
+                            fold_into(weights, index, into_index)
+                        }
+                        Split::Exact(amounts) => fold_into(amounts, index, into_index),
+                    }
+                    self.debtors.remove(index);
+                }
+                None => self.debtors[index] = into.to_owned(),
+            }
+        }
+
+        is_involved
+    }
+
+    /// The names of the debtees joined for display, e.g. `Thomas and Simon`.
+    pub fn debtee_names(&self) -> String {
+        join_names(
+            &self
+                .debtees
+                .iter()
+                .map(|d| d.name.as_str())
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+#[cfg(test)]
+impl Bill {
+    /// A bill in euros that one debtee paid in full and which is split equally, for tests.
+    pub fn for_test(id: u64, total: i64, debtee: &str, debtors: &[&str]) -> Bill {
+        Bill {
+            id,
+            name: format!("Bill {id}"),
+            total: Money::new(total, Currency::EUR),
+            debtees: vec![Debtee {
+                name: debtee.to_owned(),
+                amount: Money::new(total, Currency::EUR),
+            }],
+            debtors: debtors.iter().map(|d| d.to_string()).collect(),
+            split: Split::Equal,
+            exchange_rate: None,
+            date: OffsetDateTime::UNIX_EPOCH.date(),
+            category: Category::Other,
+            deleted: false,
+            replaced_by: None,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+        }
+    }
+}
+
+impl Payment {
+    /// Whether this payment was made from one of the given persons to the other.
+    pub fn is_between(&self, a: &str, b: &str) -> bool {
+        (self.from == a && self.to == b) || (self.from == b && self.to == a)
+    }
+}
+
+/// The amounts as weights for [`Money::allocate`], they are never negative.
+fn minor_units(amounts: impl Iterator<Item = Money>) -> Vec<u64> {
+    amounts.map(|a| a.minor_units().max(0) as u64).collect()
+}
+
+/// Adds the value at `index` to the one at `into_index` and removes it.
+fn fold_into<T: Copy + std::ops::AddAssign>(values: &mut Vec<T>, index: usize, into_index: usize) {
+    let value = values[index];
+    values[into_index] += value;
+    values.remove(index);
+}
+
+impl Split {
+    pub fn mode(&self) -> SplitMode {
+        match self {
+            Split::Equal => SplitMode::Equal,
+            Split::Shares(_) => SplitMode::Shares,
+            Split::Percentages(_) => SplitMode::Percentages,
+            Split::Exact(_) => SplitMode::Exact,
+        }
+    }
+}
+
+impl SplitMode {
+    pub const ALL: &'static [SplitMode] = &[
+        SplitMode::Equal,
+        SplitMode::Shares,
+        SplitMode::Percentages,
+        SplitMode::Exact,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SplitMode::Equal => "equal",
+            SplitMode::Shares => "shares",
+            SplitMode::Percentages => "percentages",
+            SplitMode::Exact => "exact",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SplitMode::Equal => "Equally",
+            SplitMode::Shares => "By shares",
+            SplitMode::Percentages => "By percentages",
+            SplitMode::Exact => "By exact amounts",
+        }
+    }
+}
+
+impl Category {
+    /// The categories every nobt can use.
+    pub const BUILT_IN: &'static [Category] = &[
+        Category::Food,
+        Category::Transport,
+        Category::Accommodation,
+        Category::Drinks,
+        Category::Activities,
+        Category::Other,
+    ];
+
+    /// How the category is stored and sent in forms, custom categories use their name.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Category::Food => "food",
+            Category::Transport => "transport",
+            Category::Accommodation => "accommodation",
+            Category::Drinks => "drinks",
+            Category::Activities => "activities",
+            Category::Other => "other",
+            Category::Custom(name) => name,
+        }
+    }
+
+    /// The name of the category, the ones of built-in categories still need to be translated.
+    pub fn label(&self) -> &str {
+        match self {
+            Category::Food => "Food",
+            Category::Transport => "Transport",
+            Category::Accommodation => "Accommodation",
+            Category::Drinks => "Drinks",
+            Category::Activities => "Activities",
+            Category::Other => "Other",
+            Category::Custom(name) => name,
+        }
+    }
+
+    /// The Material Symbol shown for bills of this category.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Category::Food => "restaurant",
+            Category::Transport => "directions_car",
+            Category::Accommodation => "hotel",
+            Category::Drinks => "local_bar",
+            Category::Activities => "local_activity",
+            Category::Other => "receipt",
+            Category::Custom(_) => "sell",
+        }
+    }
+
+    /// Reads a stored category, anything that isn't a built-in category is a custom one.
+    pub fn from_stored(value: &str) -> Category {
+        Category::BUILT_IN
+            .iter()
+            .find(|category| category.as_str() == value)
+            .cloned()
+            .unwrap_or_else(|| Category::Custom(value.to_owned()))
+    }
+}
+
+impl FromStr for SplitMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SplitMode::ALL
+            .iter()
+            .find(|m| m.as_str() == s)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("unknown split mode '{s}'"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_sum_up_to_total() {
+        for id in 0..5 {
+            let bill = Bill::for_test(id, 1001, "Thomas", &["Thomas", "Simon", "Prada"]);
+
+            let sum = bill
+                .shares()
+                .into_iter()
+                .fold(Money::zero(Currency::EUR), |sum, (_, share)| sum + share);
+
+            assert_eq!(sum, bill.total);
+        }
+    }
+
+    #[test]
+    fn leftover_rotates_with_bill_id() {
+        let shares = |id| {
+            Bill::for_test(id, 1000, "Thomas", &["Thomas", "Simon", "Prada"])
+                .shares()
+                .into_iter()
+                .map(|(_, share)| share.minor_units())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(shares(0), vec![334, 333, 333]);
+        assert_eq!(shares(1), vec![333, 334, 333]);
+        assert_eq!(shares(2), vec![333, 333, 334]);
+        assert_eq!(shares(3), vec![334, 333, 333]);
+    }
+
+    #[test]
+    fn debtee_names_are_joined() {
+        let mut bill = Bill::for_test(1, 1000, "Thomas", &["Thomas"]);
+        assert_eq!(bill.debtee_names(), "Thomas");
+
+        for name in ["Simon", "Prada"] {
+            bill.debtees.push(Debtee {
+                name: name.to_owned(),
+                amount: Money::zero(Currency::EUR),
+            });
+        }
+        assert_eq!(bill.debtee_names(), "Thomas, Simon and Prada");
+    }
+
+    #[test]
+    fn shares_follow_the_split() {
+        let shares = |split| {
+            let mut bill = Bill::for_test(1, 1000, "Thomas", &["Thomas", "Simon", "Prada"]);
+            bill.split = split;
+
+            bill.shares()
+                .into_iter()
+                .map(|(_, share)| share.minor_units())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(shares(Split::Shares(vec![2, 1, 1])), vec![500, 250, 250]);
+        assert_eq!(
+            shares(Split::Percentages(vec![5000, 2500, 2500])),
+            vec![500, 250, 250]
+        );
+        assert_eq!(
+            shares(Split::Exact(
+                [700, 200, 100]
+                    .into_iter()
+                    .map(|cents| Money::new(cents, Currency::EUR))
+                    .collect()
+            )),
+            vec![700, 200, 100]
+        );
+    }
+
+    #[test]
+    fn merged_participant_keeps_paying_and_owing_both_parts() {
+        let mut bill = Bill::for_test(1, 900, "Thomas", &["Thomas", "Simon", "Tom"]);
+        bill.debtees.push(Debtee {
+            name: "Tom".to_owned(),
+            amount: Money::new(100, Currency::EUR),
+        });
+        bill.debtees[0].amount = Money::new(800, Currency::EUR);
+
+        assert!(bill.merge_participant("Tom", "Thomas"));
+
+        assert_eq!(
+            bill.debtees,
+            vec![Debtee {
+                name: "Thomas".to_owned(),
+                amount: Money::new(900, Currency::EUR),
+            }]
+        );
+        assert_eq!(bill.debtors, vec!["Thomas", "Simon"]);
+        assert_eq!(bill.split, Split::Shares(vec![2, 1]));
+        assert!(!bill.merge_participant("Tom", "Thomas"));
+    }
+
+    #[test]
+    fn bills_in_other_currencies_are_converted() {
+        let mut bill = Bill::for_test(1, 1000, "Thomas", &["Thomas", "Simon", "Prada"]);
+        bill.total = Money::new(1000, Currency::CHF);
+        bill.debtees = vec![
+            Debtee {
+                name: "Thomas".to_owned(),
+                amount: Money::new(500, Currency::CHF),
+            },
+            Debtee {
+                name: "Simon".to_owned(),
+                amount: Money::new(500, Currency::CHF),
+            },
+        ];
+        bill.exchange_rate = Some(ExchangeRate::new(Currency::CHF, Currency::EUR, 1_040_100));
+
+        assert_eq!(bill.converted_total(), Money::new(1040, Currency::EUR));
+        assert_eq!(
+            bill.paid_amounts(),
+            vec![
+                ("Thomas", Money::new(520, Currency::EUR)),
+                ("Simon", Money::new(520, Currency::EUR))
+            ]
+        );
+        assert_eq!(
+            bill.shares(),
+            vec![
+                ("Thomas", Money::new(346, Currency::EUR)),
+                ("Simon", Money::new(347, Currency::EUR)),
+                ("Prada", Money::new(347, Currency::EUR))
+            ]
+        );
+    }
+
+    #[test]
+    fn category_totals_only_count_active_bills() {
+        let mut food = Bill::for_test(0, 1000, "Thomas", &["Thomas"]);
+        food.category = Category::Food;
+        let mut snacks = Bill::for_test(1, 500, "Thomas", &["Thomas"]);
+        snacks.category = Category::Food;
+        let mut deleted = Bill::for_test(2, 9000, "Thomas", &["Thomas"]);
+        deleted.category = Category::Food;
+        deleted.deleted = true;
+        let mut skiing = Bill::for_test(3, 2000, "Thomas", &["Thomas"]);
+        skiing.category = Category::Custom("Skiing".to_owned());
+        let nobt = Nobt {
+            id: "abc".to_owned(),
+            title: "Swedish Shenanigans".to_owned(),
+            currency: Currency::EUR,
+            participants: Vec::new(),
+            bills: vec![food, snacks, deleted, skiing],
+            payments: Vec::new(),
+        };
+
+        assert_eq!(
+            nobt.category_totals(),
+            vec![
+                (
+                    &Category::Custom("Skiing".to_owned()),
+                    Money::new(2000, Currency::EUR)
+                ),
+                (&Category::Food, Money::new(1500, Currency::EUR)),
+            ]
+        );
+        assert_eq!(nobt.custom_categories(), vec!["Skiing"]);
+        assert_eq!(Category::from_stored("food"), Category::Food);
+        assert_eq!(
+            Category::from_stored("Skiing"),
+            Category::Custom("Skiing".to_owned())
+        );
+    }
+
+    #[test]
+    fn ids_are_parsed_from_their_human_friendly_form() {
+        let id = new_id();
+
+        assert_eq!(parse_id(&id), Some(id.clone()));
+        assert_eq!(parse_id(&display_id(&id)), Some(id.clone()));
+        assert_eq!(
+            parse_id("K3F9-X2MQ-7HTA-C4VW"),
+            Some("k3f9x2mq7htac4vw".to_owned())
+        );
+        assert_eq!(
+            parse_id("k3f9-x2mq-7hta-oilw"),
+            Some("k3f9x2mq7hta011w".to_owned())
+        );
+        assert_eq!(parse_id("a1B2c3D4e5F6"), Some("a1B2c3D4e5F6".to_owned()));
+
+        assert_eq!(parse_id("create"), None);
+        assert_eq!(parse_id("k3f9-x2mq-7hta-c4v"), None);
+        assert_eq!(parse_id("k3f9-x2mq-7hta-c4vu"), None);
+    }
+}
@@ -1,5 +1,5 @@
 use axum::body::{Bytes, Full};
-use axum::http::{header, HeaderValue};
+use axum::http::{header, HeaderValue, StatusCode};
 use axum::response::{IntoResponse, Response};
 
 macro_rules! mime_response {
@@ -29,3 +29,18 @@ mime_response!(Jpeg, mime::IMAGE_JPEG);
 mime_response!(Css, mime::TEXT_CSS);
 mime_response!(Javascript, mime::APPLICATION_JAVASCRIPT);
 mime_response!(Png, mime::IMAGE_PNG);
+
+/// Redirects the user to the given location.
+///
+/// This is a 303 See Other which is the appropriate way of sending the user somewhere else
+/// after a successful POST request.
+///
+/// See <https://www.rfc-editor.org/rfc/rfc9110.html#name-303-see-other>.
+#[derive(Clone, Debug)]
+pub struct SeeOther(pub String);
+
+impl IntoResponse for SeeOther {
+    fn into_response(self) -> Response {
+        (StatusCode::SEE_OTHER, [(header::LOCATION, self.0)]).into_response()
+    }
+}
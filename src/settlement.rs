@@ -0,0 +1,120 @@
+use crate::balance::Balance;
+use crate::money::Money;
+
+/// A payment from one participant to another that is necessary to settle up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    pub from: String,
+    pub to: String,
+    pub amount: Money,
+}
+
+/// Computes a small set of transfers that brings all balances to zero.
+///
+/// This greedily matches the participant who owes the most with the participant who gets the most back.
+/// Each transfer settles at least one of the two, hence there are never more transfers than participants minus one.
+pub fn settle(balances: &[Balance]) -> Vec<Transfer> {
+    let mut debtors = Vec::new();
+    let mut creditors = Vec::new();
+
+    for balance in balances {
+        if balance.amount.is_negative() {
+            debtors.push((balance.name.as_str(), -balance.amount));
+        } else if balance.amount.is_positive() {
+            creditors.push((balance.name.as_str(), balance.amount));
+        }
+    }
+
+    let mut transfers = Vec::new();
+
+    loop {
+        // Stable sort to keep the order of participants for equal amounts.
+        debtors.sort_by_key(|(_, amount)| -amount.minor_units());
+        creditors.sort_by_key(|(_, amount)| -amount.minor_units());
+
+        let (Some((from, owed)), Some((to, owing))) = (debtors.first_mut(), creditors.first_mut())
+        else {
+            break;
+        };
+
+        let amount = if owed.minor_units() < owing.minor_units() {
+            *owed
+        } else {
+            *owing
+        };
+        *owed -= amount;
+        *owing -= amount;
+
+        transfers.push(Transfer {
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+        });
+
+        debtors.retain(|(_, amount)| amount.is_positive());
+        creditors.retain(|(_, amount)| amount.is_positive());
+    }
+
+    transfers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::money::Currency;
+
+    #[test]
+    fn largest_debtor_pays_largest_creditor_first() {
+        let transfers = settle(&[
+            balance("Thomas", 39034),
+            balance("Simon", -9966),
+            balance("Prada", -29068),
+            balance("Benji", 0),
+        ]);
+
+        assert_eq!(
+            transfers,
+            vec![
+                transfer("Prada", "Thomas", 29068),
+                transfer("Simon", "Thomas", 9966)
+            ]
+        );
+    }
+
+    #[test]
+    fn debts_are_split_across_creditors() {
+        let transfers = settle(&[
+            balance("Thomas", 3000),
+            balance("Simon", 1000),
+            balance("Prada", -4000),
+        ]);
+
+        assert_eq!(
+            transfers,
+            vec![
+                transfer("Prada", "Thomas", 3000),
+                transfer("Prada", "Simon", 1000)
+            ]
+        );
+    }
+
+    #[test]
+    fn nothing_to_do_if_everyone_is_settled() {
+        assert!(settle(&[balance("Thomas", 0), balance("Simon", 0)]).is_empty());
+    }
+
+    fn balance(name: &str, cents: i64) -> Balance {
+        Balance {
+            name: name.to_owned(),
+            amount: Money::new(cents, Currency::EUR),
+        }
+    }
+
+    fn transfer(from: &str, to: &str, cents: i64) -> Transfer {
+        Transfer {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            amount: Money::new(cents, Currency::EUR),
+        }
+    }
+}
@@ -0,0 +1,1290 @@
+use crate::activity::{Activity, Event};
+use crate::format;
+use crate::money::{Currency, ExchangeRate, Money};
+use crate::nobt::{
+    Bill, Category, Debtee, NewBill, NewNobt, NewPayment, Nobt, Participant, Payment, Split,
+    SplitMode,
+};
+use crate::storage::Storage;
+use anyhow::{Context, Result};
+use rusqlite::types::{ToSql, Type};
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction};
+use std::path::Path;
+use std::sync::Mutex;
+use time::{Date, OffsetDateTime};
+
+/// All schema migrations, in the order they have to be applied.
+///
+/// The index of a migration in this list is its version, the database tracks which version it is on via `PRAGMA user_version`.
+/// Migrations must never be changed once released, add a new one instead.
+const MIGRATIONS: &[&str] = &[
+    include_str!("../migrations/0001_initial_schema.sql"),
+    include_str!("../migrations/0002_payments.sql"),
+    include_str!("../migrations/0003_integer_amounts.sql"),
+    include_str!("../migrations/0004_unequal_splits.sql"),
+    include_str!("../migrations/0005_multiple_debtees.sql"),
+    include_str!("../migrations/0006_bill_revisions.sql"),
+    include_str!("../migrations/0007_foreign_currencies.sql"),
+    include_str!("../migrations/0008_bill_dates.sql"),
+    include_str!("../migrations/0009_bill_categories.sql"),
+    include_str!("../migrations/0010_activity_log.sql"),
+];
+
+/// Stores nobts in an embedded SQLite database.
+pub struct SqliteStorage {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    /// Opens (or creates) the database at the given path and applies all pending migrations.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let connection = Connection::open(path)
+            .with_context(|| format!("failed to open database at {}", path.display()))?;
+
+        Self::new(connection)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::new(Connection::open_in_memory()?)
+    }
+
+    fn new(mut connection: Connection) -> Result<Self> {
+        connection.pragma_update(None, "foreign_keys", "ON")?;
+        migrate(&mut connection)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn connection(&self) -> std::sync::MutexGuard<'_, Connection> {
+        self.connection.lock().expect("lock is never poisoned")
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn create_nobt(&self, id: &str, nobt: NewNobt) -> Result<()> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO nobts (id, title, currency) VALUES (?1, ?2, ?3)",
+            params![id, nobt.title, nobt.currency.code()],
+        )?;
+        {
+            let mut add_participant =
+                transaction.prepare("INSERT INTO participants (nobt_id, name) VALUES (?1, ?2)")?;
+            for name in &nobt.participants {
+                add_participant.execute(params![id, name])?;
+            }
+        }
+        transaction.commit()?;
+
+        Ok(())
+    }
+
+    fn get_nobt(&self, id: &str) -> Result<Option<Nobt>> {
+        load_nobt(&self.connection(), id)
+    }
+
+    fn add_participant(&self, nobt_id: &str, name: &str, actor: Option<&str>) -> Result<bool> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let inserted = transaction.execute(
+            "INSERT OR IGNORE INTO participants (nobt_id, name) VALUES (?1, ?2)",
+            params![nobt_id, name],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+        let event = Event::ParticipantAdded {
+            name: name.to_owned(),
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(true)
+    }
+
+    fn rename_participant(
+        &self,
+        nobt_id: &str,
+        name: &str,
+        new_name: &str,
+        actor: Option<&str>,
+    ) -> Result<bool> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        // Removed participants still appear in bills and payments, renaming someone to their name would mix them up.
+        let is_name_used = transaction.query_row(
+            "SELECT EXISTS (SELECT 1 FROM participants WHERE nobt_id = ?1 AND name = ?2)
+                OR EXISTS (SELECT 1 FROM bill_debtees WHERE name = ?2 AND bill_id IN (SELECT id FROM bills WHERE nobt_id = ?1))
+                OR EXISTS (SELECT 1 FROM bill_debtors WHERE name = ?2 AND bill_id IN (SELECT id FROM bills WHERE nobt_id = ?1))
+                OR EXISTS (SELECT 1 FROM payments WHERE nobt_id = ?1 AND (sender = ?2 OR recipient = ?2))",
+            params![nobt_id, new_name],
+            |row| row.get::<_, bool>(0),
+        )?;
+        if is_name_used {
+            return Ok(false);
+        }
+
+        let renamed = transaction.execute(
+            "UPDATE participants SET name = ?3 WHERE nobt_id = ?1 AND name = ?2",
+            params![nobt_id, name, new_name],
+        )?;
+        if renamed == 0 {
+            return Ok(false);
+        }
+
+        for statement in [
+            "UPDATE bill_debtees SET name = ?3 WHERE name = ?2 AND bill_id IN (SELECT id FROM bills WHERE nobt_id = ?1)",
+            "UPDATE bill_debtors SET name = ?3 WHERE name = ?2 AND bill_id IN (SELECT id FROM bills WHERE nobt_id = ?1)",
+            "UPDATE payments SET sender = ?3 WHERE nobt_id = ?1 AND sender = ?2",
+            "UPDATE payments SET recipient = ?3 WHERE nobt_id = ?1 AND recipient = ?2",
+        ] {
+            transaction.execute(statement, params![nobt_id, name, new_name])?;
+        }
+        let event = Event::ParticipantRenamed {
+            name: name.to_owned(),
+            new_name: new_name.to_owned(),
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(true)
+    }
+
+    fn remove_participant(&self, nobt_id: &str, name: &str, actor: Option<&str>) -> Result<bool> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let removed = transaction.execute(
+            "DELETE FROM participants WHERE nobt_id = ?1 AND name = ?2",
+            params![nobt_id, name],
+        )?;
+        if removed == 0 {
+            return Ok(false);
+        }
+        let event = Event::ParticipantRemoved {
+            name: name.to_owned(),
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(true)
+    }
+
+    fn merge_participants(
+        &self,
+        nobt_id: &str,
+        name: &str,
+        into: &str,
+        actor: Option<&str>,
+    ) -> Result<bool> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let Some(mut nobt) = load_nobt(&transaction, nobt_id)? else {
+            return Ok(false);
+        };
+        if name == into || !nobt.has_participant(name) || !nobt.has_participant(into) {
+            return Ok(false);
+        }
+
+        for bill in &mut nobt.bills {
+            if !bill.merge_participant(name, into) {
+                continue;
+            }
+
+            transaction.execute(
+                "UPDATE bills SET split_mode = ?2 WHERE id = ?1",
+                params![bill.id, bill.split.mode().as_str()],
+            )?;
+            transaction.execute(
+                "DELETE FROM bill_debtees WHERE bill_id = ?1",
+                params![bill.id],
+            )?;
+            transaction.execute(
+                "DELETE FROM bill_debtors WHERE bill_id = ?1",
+                params![bill.id],
+            )?;
+            insert_debtees_and_debtors(
+                &transaction,
+                nobt_id,
+                bill.id,
+                &bill.debtees,
+                &bill.debtors,
+                &bill.split,
+            )?;
+        }
+
+        for statement in [
+            "DELETE FROM payments WHERE nobt_id = ?1 AND ((sender = ?2 AND recipient = ?3) OR (sender = ?3 AND recipient = ?2))",
+            "UPDATE payments SET sender = ?3 WHERE nobt_id = ?1 AND sender = ?2",
+            "UPDATE payments SET recipient = ?3 WHERE nobt_id = ?1 AND recipient = ?2",
+        ] {
+            transaction.execute(statement, params![nobt_id, name, into])?;
+        }
+        transaction.execute(
+            "DELETE FROM participants WHERE nobt_id = ?1 AND name = ?2",
+            params![nobt_id, name],
+        )?;
+        let event = Event::ParticipantsMerged {
+            name: name.to_owned(),
+            into: into.to_owned(),
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(true)
+    }
+
+    fn add_bill(&self, nobt_id: &str, bill: NewBill, actor: Option<&str>) -> Result<u64> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let bill_id = insert_bill(&transaction, nobt_id, &bill, OffsetDateTime::now_utc())?;
+        let event = Event::BillAdded {
+            bill_id,
+            name: bill.name,
+            total: bill.total,
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(bill_id)
+    }
+
+    fn revise_bill(
+        &self,
+        nobt_id: &str,
+        bill_id: u64,
+        bill: NewBill,
+        actor: Option<&str>,
+    ) -> Result<Option<u64>> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let Some(created_at) = transaction
+            .query_row(
+                "SELECT created_at FROM bills WHERE id = ?1 AND nobt_id = ?2 AND replaced_by IS NULL AND deleted = 0",
+                params![bill_id, nobt_id],
+                |row| row.get::<_, OffsetDateTime>(0),
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        // The revision keeps the position of the bill in the nobt.
+        let revision_id = insert_bill(&transaction, nobt_id, &bill, created_at)?;
+        transaction.execute(
+            "UPDATE bills SET replaced_by = ?1 WHERE id = ?2",
+            params![revision_id, bill_id],
+        )?;
+        let event = Event::BillEdited {
+            bill_id: revision_id,
+            name: bill.name,
+            total: bill.total,
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(Some(revision_id))
+    }
+
+    fn set_bill_deleted(
+        &self,
+        nobt_id: &str,
+        bill_id: u64,
+        deleted: bool,
+        actor: Option<&str>,
+    ) -> Result<bool> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        let updated = transaction.execute(
+            "UPDATE bills SET deleted = ?1 WHERE id = ?2 AND nobt_id = ?3 AND replaced_by IS NULL AND deleted != ?1",
+            params![deleted, bill_id, nobt_id],
+        )?;
+        if updated == 0 {
+            return Ok(false);
+        }
+
+        // Bills in another currency than the one of their nobt store their amounts in that currency.
+        let (name, minor_units, currency) = transaction.query_row(
+            "SELECT bills.name, bills.total_minor_units, COALESCE(bills.currency, nobts.currency) FROM bills JOIN nobts ON nobts.id = bills.nobt_id WHERE bills.id = ?1",
+            params![bill_id],
+            |row| Ok((row.get::<_, String>(0)?, row.get(1)?, row.get::<_, String>(2)?)),
+        )?;
+        let total = Money::new(minor_units, currency.parse()?);
+        let event = if deleted {
+            Event::BillDeleted {
+                bill_id,
+                name,
+                total,
+            }
+        } else {
+            Event::BillRestored {
+                bill_id,
+                name,
+                total,
+            }
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(true)
+    }
+
+    fn add_payment(&self, nobt_id: &str, payment: NewPayment, actor: Option<&str>) -> Result<u64> {
+        let mut connection = self.connection();
+        let transaction = connection.transaction()?;
+
+        transaction.execute(
+            "INSERT INTO payments (nobt_id, sender, recipient, amount_minor_units, note, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                nobt_id,
+                payment.from,
+                payment.to,
+                payment.amount.minor_units(),
+                payment.note,
+                OffsetDateTime::now_utc()
+            ],
+        )?;
+        let payment_id = transaction.last_insert_rowid() as u64;
+        let event = Event::PaymentAdded {
+            from: payment.from,
+            to: payment.to,
+            amount: payment.amount,
+        };
+        log_activity(&transaction, nobt_id, actor, &event)?;
+        transaction.commit()?;
+
+        Ok(payment_id)
+    }
+
+    fn activities(&self, nobt_id: &str) -> Result<Vec<Activity>> {
+        select_activities(&self.connection(), "nobt_id = ?", &[&nobt_id])
+    }
+
+    fn bill_activities(&self, nobt_id: &str, bill_ids: &[u64]) -> Result<Vec<Activity>> {
+        let condition = format!(
+            "nobt_id = ? AND bill_id IN ({})",
+            vec!["?"; bill_ids.len()].join(", ")
+        );
+        let mut values = vec![&nobt_id as &dyn ToSql];
+        values.extend(bill_ids.iter().map(|id| id as &dyn ToSql));
+
+        select_activities(&self.connection(), &condition, &values)
+    }
+
+    fn exchange_rate(&self, from: Currency, to: Currency) -> Result<Option<ExchangeRate>> {
+        let millionths = self
+            .connection()
+            .query_row(
+                "SELECT millionths FROM exchange_rates WHERE from_currency = ?1 AND to_currency = ?2",
+                params![from.code(), to.code()],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(millionths.map(|millionths| ExchangeRate::new(from, to, millionths)))
+    }
+}
+
+/// Reads a nobt with its participants, bills and payments.
+fn load_nobt(connection: &Connection, id: &str) -> Result<Option<Nobt>> {
+    let Some((title, currency)) = connection
+        .query_row(
+            "SELECT title, currency FROM nobts WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get::<_, String>(1)?)),
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let currency = currency.parse::<Currency>()?;
+
+    let participants = connection
+        .prepare("SELECT name FROM participants WHERE nobt_id = ?1 ORDER BY rowid")?
+        .query_map(params![id], |row| Ok(Participant { name: row.get(0)? }))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let bills = connection
+        .prepare(
+            "SELECT id, name, total_minor_units, deleted, replaced_by, created_at, split_mode, currency, exchange_rate_millionths, date, category FROM bills WHERE nobt_id = ?1 ORDER BY id",
+        )?
+        .query_map(params![id], |row| {
+            let bill = Bill {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                total: Money::new(row.get(2)?, currency),
+                debtees: Vec::new(),
+                debtors: Vec::new(),
+                split: Split::Equal,
+                exchange_rate: None,
+                date: date_column(row, 9)?,
+                category: Category::from_stored(&row.get::<_, String>(10)?),
+                deleted: row.get(3)?,
+                replaced_by: row.get(4)?,
+                created_at: row.get(5)?,
+            };
+
+            Ok((
+                bill,
+                row.get::<_, String>(6)?,
+                row.get::<_, Option<String>>(7)?,
+                row.get::<_, Option<i64>>(8)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut debtees = connection.prepare(
+        "SELECT name, amount_minor_units FROM bill_debtees WHERE bill_id = ?1 ORDER BY rowid",
+    )?;
+    let mut debtors = connection
+        .prepare("SELECT name, weight FROM bill_debtors WHERE bill_id = ?1 ORDER BY rowid")?;
+    let bills = bills
+        .into_iter()
+        .map(|(mut bill, split_mode, bill_currency, exchange_rate)| {
+            // Bills without a currency of their own are in the currency of the nobt.
+            let bill_currency = match bill_currency {
+                Some(bill_currency) => bill_currency.parse::<Currency>()?,
+                None => currency,
+            };
+            bill.total = Money::new(bill.total.minor_units(), bill_currency);
+            bill.exchange_rate = exchange_rate
+                .map(|millionths| ExchangeRate::new(bill_currency, currency, millionths));
+
+            bill.debtees = debtees
+                .query_map(params![bill.id], |row| {
+                    Ok(Debtee {
+                        name: row.get(0)?,
+                        amount: Money::new(row.get(1)?, bill_currency),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let (names, weights) = debtors
+                .query_map(params![bill.id], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+                })?
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip::<_, _, Vec<_>, Vec<_>>();
+
+            bill.debtors = names;
+            bill.split = split(split_mode.parse()?, weights, bill_currency)
+                .with_context(|| format!("invalid split of bill {}", bill.id))?;
+
+            Ok(bill)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let payments = connection
+        .prepare(
+            "SELECT sender, recipient, amount_minor_units, note, created_at FROM payments WHERE nobt_id = ?1 ORDER BY id",
+        )?
+        .query_map(params![id], |row| {
+            Ok(Payment {
+                from: row.get(0)?,
+                to: row.get(1)?,
+                amount: Money::new(row.get(2)?, currency),
+                note: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(Nobt {
+        id: id.to_owned(),
+        title,
+        currency,
+        participants,
+        bills,
+        payments,
+    }))
+}
+
+/// Reads a date stored in the ISO 8601 format, e.g. `2022-08-28`.
+fn date_column(row: &Row, index: usize) -> rusqlite::Result<Date> {
+    let text = row.get::<_, String>(index)?;
+
+    format::parse_iso_date(&text).ok_or_else(|| {
+        rusqlite::Error::FromSqlConversionFailure(
+            index,
+            Type::Text,
+            format!("invalid date '{text}'").into(),
+        )
+    })
+}
+
+/// Reads the entries of the activity log that match the condition, oldest first.
+fn select_activities(
+    connection: &Connection,
+    condition: &str,
+    values: &[&dyn ToSql],
+) -> Result<Vec<Activity>> {
+    let rows = connection
+        .prepare(&format!(
+            "SELECT id, actor, kind, bill_id, subject, detail, amount_minor_units, currency, created_at FROM activities WHERE {condition} ORDER BY id"
+        ))?
+        .query_map(values, |row| {
+            Ok((
+                row.get::<_, u64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<u64>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<i64>>(6)?,
+                row.get::<_, Option<String>>(7)?,
+                row.get::<_, OffsetDateTime>(8)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    rows.into_iter()
+        .map(
+            |(id, actor, kind, bill_id, subject, detail, minor_units, currency, created_at)| {
+                let amount = match (minor_units, currency) {
+                    (Some(minor_units), Some(currency)) => {
+                        Some(Money::new(minor_units, currency.parse()?))
+                    }
+                    _ => None,
+                };
+                let missing = |column: &str| {
+                    anyhow::anyhow!("activity {id} of kind '{kind}' has no {column}")
+                };
+
+                let event = match kind.as_str() {
+                    "bill_added" | "bill_edited" | "bill_deleted" | "bill_restored" => {
+                        let bill_id = bill_id.ok_or_else(|| missing("bill"))?;
+                        let total = amount.ok_or_else(|| missing("amount"))?;
+                        let name = subject;
+                        match kind.as_str() {
+                            "bill_added" => Event::BillAdded {
+                                bill_id,
+                                name,
+                                total,
+                            },
+                            "bill_edited" => Event::BillEdited {
+                                bill_id,
+                                name,
+                                total,
+                            },
+                            "bill_deleted" => Event::BillDeleted {
+                                bill_id,
+                                name,
+                                total,
+                            },
+                            _ => Event::BillRestored {
+                                bill_id,
+                                name,
+                                total,
+                            },
+                        }
+                    }
+                    "payment_added" => Event::PaymentAdded {
+                        from: subject,
+                        to: detail.ok_or_else(|| missing("recipient"))?,
+                        amount: amount.ok_or_else(|| missing("amount"))?,
+                    },
+                    "participant_added" => Event::ParticipantAdded { name: subject },
+                    "participant_renamed" => Event::ParticipantRenamed {
+                        name: subject,
+                        new_name: detail.ok_or_else(|| missing("new name"))?,
+                    },
+                    "participant_removed" => Event::ParticipantRemoved { name: subject },
+                    "participants_merged" => Event::ParticipantsMerged {
+                        name: subject,
+                        into: detail.ok_or_else(|| missing("participant merged into"))?,
+                    },
+                    _ => anyhow::bail!("activity {id} is of unknown kind '{kind}'"),
+                };
+
+                Ok(Activity {
+                    actor,
+                    event,
+                    created_at,
+                })
+            },
+        )
+        .collect()
+}
+
+/// Appends an event to the activity log, in the transaction of the change it records.
+fn log_activity(
+    transaction: &Transaction,
+    nobt_id: &str,
+    actor: Option<&str>,
+    event: &Event,
+) -> Result<()> {
+    let (kind, bill_id, subject, detail, amount) = match event {
+        Event::BillAdded {
+            bill_id,
+            name,
+            total,
+        } => ("bill_added", Some(bill_id), name, None, Some(total)),
+        Event::BillEdited {
+            bill_id,
+            name,
+            total,
+        } => ("bill_edited", Some(bill_id), name, None, Some(total)),
+        Event::BillDeleted {
+            bill_id,
+            name,
+            total,
+        } => ("bill_deleted", Some(bill_id), name, None, Some(total)),
+        Event::BillRestored {
+            bill_id,
+            name,
+            total,
+        } => ("bill_restored", Some(bill_id), name, None, Some(total)),
+        Event::PaymentAdded { from, to, amount } => {
+            ("payment_added", None, from, Some(to), Some(amount))
+        }
+        Event::ParticipantAdded { name } => ("participant_added", None, name, None, None),
+        Event::ParticipantRenamed { name, new_name } => {
+            ("participant_renamed", None, name, Some(new_name), None)
+        }
+        Event::ParticipantRemoved { name } => ("participant_removed", None, name, None, None),
+        Event::ParticipantsMerged { name, into } => {
+            ("participants_merged", None, name, Some(into), None)
+        }
+    };
+
+    transaction.execute(
+        "INSERT INTO activities (nobt_id, actor, kind, bill_id, subject, detail, amount_minor_units, currency, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            nobt_id,
+            actor,
+            kind,
+            bill_id,
+            subject,
+            detail,
+            amount.map(|amount| amount.minor_units()),
+            amount.map(|amount| amount.currency().code()),
+            OffsetDateTime::now_utc()
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Inserts a bill and its debtees and debtors, returning the ID of the new bill.
+fn insert_bill(
+    transaction: &Transaction,
+    nobt_id: &str,
+    bill: &NewBill,
+    created_at: OffsetDateTime,
+) -> Result<u64> {
+    transaction.execute(
+        "INSERT INTO bills (nobt_id, name, total_minor_units, split_mode, created_at, currency, exchange_rate_millionths, date, category) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            nobt_id,
+            bill.name,
+            bill.total.minor_units(),
+            bill.split.mode().as_str(),
+            created_at,
+            bill.exchange_rate.map(|rate| rate.from().code()),
+            bill.exchange_rate.map(|rate| rate.millionths()),
+            bill.date.to_string(),
+            bill.category.as_str(),
+        ],
+    )?;
+    let bill_id = transaction.last_insert_rowid() as u64;
+
+    insert_debtees_and_debtors(
+        transaction,
+        nobt_id,
+        bill_id,
+        &bill.debtees,
+        &bill.debtors,
+        &bill.split,
+    )?;
+
+    Ok(bill_id)
+}
+
+/// Inserts who paid and who owes a bill, adding them to the participants of the nobt if necessary.
+fn insert_debtees_and_debtors(
+    transaction: &Transaction,
+    nobt_id: &str,
+    bill_id: u64,
+    debtees: &[Debtee],
+    debtors: &[String],
+    split: &Split,
+) -> Result<()> {
+    let weights = match split {
+        Split::Equal => vec![None; debtors.len()],
+        Split::Shares(weights) | Split::Percentages(weights) => {
+            weights.iter().map(|w| Some(*w as i64)).collect()
+        }
+        Split::Exact(amounts) => amounts.iter().map(|a| Some(a.minor_units())).collect(),
+    };
+
+    {
+        let mut add_participant = transaction
+            .prepare("INSERT OR IGNORE INTO participants (nobt_id, name) VALUES (?1, ?2)")?;
+        let mut add_debtee = transaction.prepare(
+            "INSERT INTO bill_debtees (bill_id, name, amount_minor_units) VALUES (?1, ?2, ?3)",
+        )?;
+        let mut add_debtor = transaction
+            .prepare("INSERT INTO bill_debtors (bill_id, name, weight) VALUES (?1, ?2, ?3)")?;
+
+        for debtee in debtees {
+            add_participant.execute(params![nobt_id, debtee.name])?;
+            add_debtee.execute(params![bill_id, debtee.name, debtee.amount.minor_units()])?;
+        }
+        for (debtor, weight) in debtors.iter().zip(weights) {
+            add_participant.execute(params![nobt_id, debtor])?;
+            add_debtor.execute(params![bill_id, debtor, weight])?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Restores the split of a bill from its split mode and the weights of its debtors.
+fn split(mode: SplitMode, weights: Vec<Option<i64>>, currency: Currency) -> Result<Split> {
+    if mode == SplitMode::Equal {
+        return Ok(Split::Equal);
+    }
+
+    let weights = weights
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .context("debtor without weight")?;
+
+    Ok(match mode {
+        SplitMode::Equal => Split::Equal,
+        SplitMode::Shares => Split::Shares(weights.into_iter().map(|w| w as u64).collect()),
+        SplitMode::Percentages => {
+            Split::Percentages(weights.into_iter().map(|w| w as u64).collect())
+        }
+        SplitMode::Exact => Split::Exact(
+            weights
+                .into_iter()
+                .map(|w| Money::new(w, currency))
+                .collect(),
+        ),
+    })
+}
+
+/// Applies all migrations the database has not seen yet, each in its own transaction.
+fn migrate(connection: &mut Connection) -> Result<()> {
+    let version = connection.query_row("PRAGMA user_version", [], |row| row.get::<_, usize>(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let version = index + 1;
+
+        let transaction = connection.transaction()?;
+        transaction
+            .execute_batch(migration)
+            .with_context(|| format!("failed to apply migration {version}"))?;
+        transaction.pragma_update(None, "user_version", version)?;
+        transaction.commit()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::Month;
+
+    #[test]
+    fn migrating_twice_is_a_no_op() {
+        let mut connection = Connection::open_in_memory().unwrap();
+
+        migrate(&mut connection).unwrap();
+        migrate(&mut connection).unwrap();
+
+        let version = connection
+            .query_row("PRAGMA user_version", [], |row| row.get::<_, usize>(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn added_bill_is_part_of_nobt() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+
+        let bill_id = storage.add_bill("abc", taxi(), None).unwrap();
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        let bill = nobt.bill(bill_id).unwrap();
+        assert_eq!(bill.name, "Taxi zum Club");
+        assert_eq!(bill.debtees, taxi().debtees);
+        assert_eq!(bill.debtors, vec!["Thomas", "Simon"]);
+        assert_eq!(
+            nobt.participant_names().collect::<Vec<_>>(),
+            vec!["Thomas", "Simon"]
+        );
+    }
+
+    #[test]
+    fn split_of_bill_is_stored() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        let split = Split::Exact(vec![
+            Money::new(2000, Currency::EUR),
+            Money::new(1300, Currency::EUR),
+        ]);
+
+        let bill_id = storage
+            .add_bill(
+                "abc",
+                NewBill {
+                    split: split.clone(),
+                    ..taxi()
+                },
+                None,
+            )
+            .unwrap();
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        assert_eq!(nobt.bill(bill_id).unwrap().split, split);
+    }
+
+    #[test]
+    fn bill_in_other_currency_keeps_its_amounts() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        let rate = ExchangeRate::new(Currency::JPY, Currency::EUR, 6125);
+        let bill = NewBill {
+            total: Money::new(2000, Currency::JPY),
+            debtees: vec![Debtee {
+                name: "Simon".to_owned(),
+                amount: Money::new(2000, Currency::JPY),
+            }],
+            split: Split::Exact(vec![
+                Money::new(1500, Currency::JPY),
+                Money::new(500, Currency::JPY),
+            ]),
+            exchange_rate: Some(rate),
+            ..taxi()
+        };
+
+        let bill_id = storage.add_bill("abc", bill.clone(), None).unwrap();
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        let stored = nobt.bill(bill_id).unwrap();
+        assert_eq!(stored.total, bill.total);
+        assert_eq!(stored.debtees, bill.debtees);
+        assert_eq!(stored.split, bill.split);
+        assert_eq!(stored.exchange_rate, Some(rate));
+    }
+
+    #[test]
+    fn bills_keep_their_category() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        let skiing = Category::Custom("Skiing".to_owned());
+
+        let taxi_id = storage.add_bill("abc", taxi(), None).unwrap();
+        let lift_id = storage
+            .add_bill(
+                "abc",
+                NewBill {
+                    category: skiing.clone(),
+                    ..taxi()
+                },
+                None,
+            )
+            .unwrap();
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        assert_eq!(nobt.bill(taxi_id).unwrap().category, Category::Transport);
+        assert_eq!(nobt.bill(lift_id).unwrap().category, skiing);
+    }
+
+    #[test]
+    fn exchange_rates_come_from_the_rate_table() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        storage
+            .connection()
+            .execute(
+                "INSERT INTO exchange_rates (from_currency, to_currency, millionths) VALUES ('CHF', 'EUR', 1040000)",
+                [],
+            )
+            .unwrap();
+
+        assert_eq!(
+            storage.exchange_rate(Currency::CHF, Currency::EUR).unwrap(),
+            Some(ExchangeRate::new(Currency::CHF, Currency::EUR, 1_040_000))
+        );
+        assert_eq!(
+            storage.exchange_rate(Currency::EUR, Currency::CHF).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn revision_replaces_bill() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        let bill_id = storage.add_bill("abc", taxi(), None).unwrap();
+
+        let revision_id = storage
+            .revise_bill(
+                "abc",
+                bill_id,
+                NewBill {
+                    name: "Taxi zur Bar".to_owned(),
+                    ..taxi()
+                },
+                None,
+            )
+            .unwrap()
+            .unwrap();
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        let bill = nobt.bill(bill_id).unwrap();
+        assert_eq!(bill.replaced_by, Some(revision_id));
+        assert_eq!(nobt.latest_revision(bill).name, "Taxi zur Bar");
+        assert_eq!(nobt.latest_revision(bill).date, taxi().date);
+        assert_eq!(nobt.latest_revision(bill).created_at, bill.created_at);
+        assert_eq!(
+            nobt.active_bills().map(|b| b.id).collect::<Vec<_>>(),
+            vec![revision_id]
+        );
+
+        assert_eq!(
+            storage.revise_bill("abc", bill_id, taxi(), None).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn deleted_bill_cannot_be_revised() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        let bill_id = storage.add_bill("abc", taxi(), None).unwrap();
+        storage
+            .set_bill_deleted("abc", bill_id, true, None)
+            .unwrap();
+
+        assert_eq!(
+            storage.revise_bill("abc", bill_id, taxi(), None).unwrap(),
+            None
+        );
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        assert_eq!(nobt.active_bills().count(), 0);
+    }
+
+    #[test]
+    fn renaming_participant_updates_bills_and_payments() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        let bill_id = storage.add_bill("abc", taxi(), None).unwrap();
+        storage
+            .add_payment(
+                "abc",
+                NewPayment {
+                    from: "Thomas".to_owned(),
+                    to: "Simon".to_owned(),
+                    amount: Money::new(1650, Currency::EUR),
+                    note: None,
+                },
+                None,
+            )
+            .unwrap();
+
+        assert!(storage
+            .rename_participant("abc", "Simon", "Simone", None)
+            .unwrap());
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        let bill = nobt.bill(bill_id).unwrap();
+        assert_eq!(bill.debtees[0].name, "Simone");
+        assert_eq!(bill.debtors, vec!["Thomas", "Simone"]);
+        assert_eq!(nobt.payments[0].to, "Simone");
+        assert_eq!(
+            nobt.participant_names().collect::<Vec<_>>(),
+            vec!["Thomas", "Simone"]
+        );
+        assert!(!storage
+            .rename_participant("abc", "Simon", "Simone", None)
+            .unwrap());
+    }
+
+    #[test]
+    fn cannot_rename_participant_to_name_of_removed_participant() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        storage.add_bill("abc", taxi(), None).unwrap();
+        storage.add_participant("abc", "Prada", None).unwrap();
+        storage.remove_participant("abc", "Simon", None).unwrap();
+
+        assert!(!storage
+            .rename_participant("abc", "Prada", "Simon", None)
+            .unwrap());
+        assert!(!storage
+            .rename_participant("abc", "Thomas", "Simon", None)
+            .unwrap());
+        assert!(storage
+            .rename_participant("abc", "Prada", "Pradas", None)
+            .unwrap());
+    }
+
+    #[test]
+    fn merging_participants_folds_bills_and_payments() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        storage.add_participant("abc", "Tom", None).unwrap();
+        let mut bill = taxi();
+        bill.debtors.push("Tom".to_owned());
+        let bill_id = storage.add_bill("abc", bill, None).unwrap();
+        for (from, to) in [("Tom", "Thomas"), ("Tom", "Simon")] {
+            storage
+                .add_payment(
+                    "abc",
+                    NewPayment {
+                        from: from.to_owned(),
+                        to: to.to_owned(),
+                        amount: Money::new(500, Currency::EUR),
+                        note: None,
+                    },
+                    None,
+                )
+                .unwrap();
+        }
+
+        assert!(storage
+            .merge_participants("abc", "Tom", "Thomas", None)
+            .unwrap());
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        let bill = nobt.bill(bill_id).unwrap();
+        assert_eq!(bill.debtors, vec!["Thomas", "Simon"]);
+        assert_eq!(bill.split, Split::Shares(vec![2, 1]));
+        assert_eq!(nobt.payments.len(), 1);
+        assert_eq!(nobt.payments[0].from, "Thomas");
+        assert!(!nobt.has_participant("Tom"));
+        assert!(!storage
+            .merge_participants("abc", "Tom", "Thomas", None)
+            .unwrap());
+    }
+
+    #[test]
+    fn merging_participants_includes_bills_added_since_nobt_was_loaded() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        storage.add_participant("abc", "Tom", None).unwrap();
+        let loaded = storage.get_nobt("abc").unwrap().unwrap();
+        let mut bill = taxi();
+        bill.debtors = vec!["Tom".to_owned()];
+        let bill_id = storage.add_bill("abc", bill, None).unwrap();
+
+        assert!(loaded.bill(bill_id).is_none());
+        assert!(storage
+            .merge_participants("abc", "Tom", "Thomas", None)
+            .unwrap());
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        assert_eq!(nobt.bill(bill_id).unwrap().debtors, vec!["Thomas"]);
+    }
+
+    #[test]
+    fn participants_can_be_added_and_removed() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+
+        assert!(storage.add_participant("abc", "Simon", None).unwrap());
+        assert!(!storage.add_participant("abc", "Simon", None).unwrap());
+        assert!(storage.remove_participant("abc", "Thomas", None).unwrap());
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        assert_eq!(nobt.participant_names().collect::<Vec<_>>(), vec!["Simon"]);
+    }
+
+    #[test]
+    fn deleted_bill_can_be_restored() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        let bill_id = storage.add_bill("abc", taxi(), None).unwrap();
+
+        assert!(storage
+            .set_bill_deleted("abc", bill_id, true, None)
+            .unwrap());
+        assert!(
+            storage
+                .get_nobt("abc")
+                .unwrap()
+                .unwrap()
+                .bill(bill_id)
+                .unwrap()
+                .deleted
+        );
+
+        assert!(storage
+            .set_bill_deleted("abc", bill_id, false, None)
+            .unwrap());
+        assert!(
+            !storage
+                .get_nobt("abc")
+                .unwrap()
+                .unwrap()
+                .bill(bill_id)
+                .unwrap()
+                .deleted
+        );
+    }
+
+    #[test]
+    fn cannot_delete_bill_of_other_nobt() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        insert_nobt(&storage, "def");
+        let bill_id = storage.add_bill("abc", taxi(), None).unwrap();
+
+        assert!(!storage
+            .set_bill_deleted("def", bill_id, true, None)
+            .unwrap());
+    }
+
+    #[test]
+    fn added_payment_is_part_of_nobt() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+
+        storage
+            .add_payment(
+                "abc",
+                NewPayment {
+                    from: "Simon".to_owned(),
+                    to: "Thomas".to_owned(),
+                    amount: Money::new(1950, Currency::EUR),
+                    note: Some("Flughafen Essen".to_owned()),
+                },
+                None,
+            )
+            .unwrap();
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        assert_eq!(nobt.payments.len(), 1);
+        assert_eq!(nobt.payments[0].amount, Money::new(1950, Currency::EUR));
+        assert_eq!(nobt.payments[0].note.as_deref(), Some("Flughafen Essen"));
+    }
+
+    #[test]
+    fn changes_are_logged_along_with_who_made_them() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+        insert_nobt(&storage, "def");
+
+        let bill_id = storage.add_bill("abc", taxi(), Some("Simon")).unwrap();
+        assert!(storage
+            .set_bill_deleted("abc", bill_id, true, None)
+            .unwrap());
+        assert!(!storage
+            .set_bill_deleted("abc", bill_id, true, None)
+            .unwrap());
+        storage.add_bill("def", taxi(), None).unwrap();
+
+        let activities = storage.activities("abc").unwrap();
+        let taxi_event = |deleted| {
+            let (name, total) = ("Taxi zum Club".to_owned(), Money::new(3300, Currency::EUR));
+            if deleted {
+                Event::BillDeleted {
+                    bill_id,
+                    name,
+                    total,
+                }
+            } else {
+                Event::BillAdded {
+                    bill_id,
+                    name,
+                    total,
+                }
+            }
+        };
+        assert_eq!(activities.len(), 2);
+        assert_eq!(activities[0].actor.as_deref(), Some("Simon"));
+        assert_eq!(activities[0].event, taxi_event(false));
+        assert_eq!(activities[1].actor, None);
+        assert_eq!(activities[1].event, taxi_event(true));
+    }
+
+    #[test]
+    fn history_of_bill_covers_its_revisions() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+
+        let bill_id = storage.add_bill("abc", taxi(), None).unwrap();
+        storage.add_bill("abc", taxi(), None).unwrap();
+        let revision_id = storage
+            .revise_bill("abc", bill_id, taxi(), Some("Thomas"))
+            .unwrap()
+            .unwrap();
+
+        let nobt = storage.get_nobt("abc").unwrap().unwrap();
+        let revisions = nobt.revisions(nobt.bill(bill_id).unwrap());
+        assert_eq!(revisions, vec![bill_id, revision_id]);
+
+        let history = storage.bill_activities("abc", &revisions).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].actor.as_deref(), Some("Thomas"));
+    }
+
+    #[test]
+    fn failed_changes_are_not_logged() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+        insert_nobt(&storage, "abc");
+
+        assert!(!storage.add_participant("abc", "Thomas", None).unwrap());
+        assert!(!storage
+            .rename_participant("abc", "Simon", "Simone", None)
+            .unwrap());
+        assert_eq!(storage.revise_bill("abc", 1, taxi(), None).unwrap(), None);
+
+        assert!(storage.activities("abc").unwrap().is_empty());
+    }
+
+    #[test]
+    fn unknown_nobt_is_none() {
+        let storage = SqliteStorage::open_in_memory().unwrap();
+
+        assert!(storage.get_nobt("unknown").unwrap().is_none());
+    }
+
+    fn insert_nobt(storage: &SqliteStorage, id: &str) {
+        storage
+            .create_nobt(
+                id,
+                NewNobt {
+                    title: "Swedish Shenanigans".to_owned(),
+                    currency: Currency::EUR,
+                    participants: vec!["Thomas".to_owned()],
+                },
+            )
+            .unwrap();
+    }
+
+    fn taxi() -> NewBill {
+        NewBill {
+            name: "Taxi zum Club".to_owned(),
+            total: Money::new(3300, Currency::EUR),
+            debtees: vec![Debtee {
+                name: "Simon".to_owned(),
+                amount: Money::new(3300, Currency::EUR),
+            }],
+            debtors: vec!["Thomas".to_owned(), "Simon".to_owned()],
+            split: Split::Equal,
+            exchange_rate: None,
+            date: Date::from_calendar_date(2022, Month::August, 28).unwrap(),
+            category: Category::Transport,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::activity::Activity;
+use crate::money::{Currency, ExchangeRate};
+use crate::nobt::{NewBill, NewNobt, NewPayment, Nobt};
+use anyhow::Result;
+
+/// Abstracts over where nobts are stored.
+///
+/// All handlers access nobts through this trait, keyed by the ID that is part of the URL.
+///
+/// Methods that change a nobt take the participant who made the change (if known) and append it to the activity log in
+/// the same transaction, so the log never misses a change nor records one that didn't happen.
+pub trait Storage: Send + Sync {
+    fn create_nobt(&self, id: &str, nobt: NewNobt) -> Result<()>;
+
+    fn get_nobt(&self, id: &str) -> Result<Option<Nobt>>;
+
+    /// Adds a participant to a nobt.
+    ///
+    /// Returns `false` if the nobt already has a participant with that name.
+    fn add_participant(&self, nobt_id: &str, name: &str, actor: Option<&str>) -> Result<bool>;
+
+    /// Renames a participant throughout the nobt, i.e. also in all bills and payments.
+    ///
+    /// Returns `false` if the nobt doesn't have such a participant or the new name is used anywhere in the nobt, see
+    /// [`Nobt::is_name_used`].
+    fn rename_participant(
+        &self,
+        nobt_id: &str,
+        name: &str,
+        new_name: &str,
+        actor: Option<&str>,
+    ) -> Result<bool>;
+
+    /// Removes a participant from a nobt.
+    ///
+    /// Bills and payments the participant was involved in are not changed.
+    /// Returns `false` if the nobt doesn't have such a participant.
+    fn remove_participant(&self, nobt_id: &str, name: &str, actor: Option<&str>) -> Result<bool>;
+
+    /// Merges the participant `name` into the participant `into`, see [`Nobt::merge_participants`].
+    ///
+    /// Returns `false` if the nobt doesn't have both participants.
+    fn merge_participants(
+        &self,
+        nobt_id: &str,
+        name: &str,
+        into: &str,
+        actor: Option<&str>,
+    ) -> Result<bool>;
+
+    /// Adds a bill to a nobt, returning the ID of the new bill.
+    ///
+    /// Everyone involved in the bill who is not yet a participant of the nobt becomes one.
+    fn add_bill(&self, nobt_id: &str, bill: NewBill, actor: Option<&str>) -> Result<u64>;
+
+    /// Stores the changes to a bill as a new revision which replaces the bill, returning the ID of the revision.
+    ///
+    /// Returns `None` if the nobt doesn't have such a bill, it has already been replaced or it is deleted.
+    fn revise_bill(
+        &self,
+        nobt_id: &str,
+        bill_id: u64,
+        bill: NewBill,
+        actor: Option<&str>,
+    ) -> Result<Option<u64>>;
+
+    /// Marks a bill as deleted or restores it.
+    ///
+    /// Returns `false` if the nobt doesn't have such a bill or it already is deleted or restored.
+    fn set_bill_deleted(
+        &self,
+        nobt_id: &str,
+        bill_id: u64,
+        deleted: bool,
+        actor: Option<&str>,
+    ) -> Result<bool>;
+
+    /// Records a payment between two participants of a nobt, returning the ID of the new payment.
+    fn add_payment(&self, nobt_id: &str, payment: NewPayment, actor: Option<&str>) -> Result<u64>;
+
+    /// The activity log of a nobt, oldest first.
+    fn activities(&self, nobt_id: &str) -> Result<Vec<Activity>>;
+
+    /// The entries of the activity log of a nobt about the given bills, oldest first.
+    ///
+    /// Pass all revisions of a bill, see [`Nobt::revisions`], to get its whole history.
+    fn bill_activities(&self, nobt_id: &str, bill_ids: &[u64]) -> Result<Vec<Activity>>;
+
+    /// The rate from the locally maintained rate table to convert one currency into another, if there is one.
+    fn exchange_rate(&self, from: Currency, to: Currency) -> Result<Option<ExchangeRate>>;
+}
@@ -0,0 +1,136 @@
+/// Validation errors of a form, keyed by the name of the field they belong to.
+///
+/// Forms are re-rendered with these errors shown next to the offending fields.
+#[derive(Debug, Default, PartialEq)]
+pub struct FieldErrors(Vec<(&'static str, &'static str)>);
+
+impl FieldErrors {
+    /// Records the error of a field, if any, and returns the valid value otherwise.
+    pub fn check<T>(&mut self, field: &'static str, result: Result<T, &'static str>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(message) => {
+                self.0.push((field, message));
+                None
+            }
+        }
+    }
+
+    /// Records an error of a field that was found outside of [`FieldErrors::check`].
+    pub fn add(&mut self, field: &'static str, message: &'static str) {
+        self.0.push((field, message));
+    }
+
+    /// The error message of a field, if it is invalid.
+    pub fn get(&self, field: &str) -> Option<&'static str> {
+        self.0
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, message)| *message)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// The longest name a person can have, in characters.
+const MAX_NAME_LENGTH: usize = 40;
+
+/// The longest title a nobt can have, in characters.
+const MAX_TITLE_LENGTH: usize = 60;
+
+/// The longest name a custom category can have, in characters.
+const MAX_CATEGORY_LENGTH: usize = 30;
+
+/// Checks that a name can be used for a person, returning the name without surrounding whitespace.
+pub fn person_name(name: &str) -> Result<&str, &'static str> {
+    checked_text(
+        name,
+        MAX_NAME_LENGTH,
+        "Names must not be empty.",
+        "Names must not be longer than 40 characters.",
+    )
+}
+
+/// Checks that a title can be used for a nobt, returning the title without surrounding whitespace.
+pub fn nobt_title(title: &str) -> Result<&str, &'static str> {
+    checked_text(
+        title,
+        MAX_TITLE_LENGTH,
+        "The nobt needs a name.",
+        "Names of nobts must not be longer than 60 characters.",
+    )
+}
+
+/// Checks that a name can be used for a custom category, returning the name without surrounding whitespace.
+pub fn category_name(name: &str) -> Result<&str, &'static str> {
+    checked_text(
+        name,
+        MAX_CATEGORY_LENGTH,
+        "Categories must not be empty.",
+        "Categories must not be longer than 30 characters.",
+    )
+}
+
+/// Trims text entered by a user and checks that it is neither empty nor too long and has no control characters.
+fn checked_text<'t>(
+    text: &'t str,
+    max_length: usize,
+    empty: &'static str,
+    too_long: &'static str,
+) -> Result<&'t str, &'static str> {
+    let text = text.trim();
+
+    if text.is_empty() {
+        return Err(empty);
+    }
+    if text.chars().count() > max_length {
+        return Err(too_long);
+    }
+    if text.chars().any(char::is_control) {
+        return Err("Names must not contain control characters.");
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_error_of_a_field_is_kept() {
+        let mut errors = FieldErrors::default();
+
+        assert_eq!(errors.check("total", Ok::<_, &str>(3)), Some(3));
+        assert_eq!(errors.check::<()>("name", Err("first")), None);
+        assert_eq!(errors.check::<()>("name", Err("second")), None);
+
+        assert_eq!(errors.get("name"), Some("first"));
+        assert_eq!(errors.get("total"), None);
+    }
+
+    #[test]
+    fn person_names_are_trimmed_and_checked() {
+        assert_eq!(person_name("  Thomas "), Ok("Thomas"));
+        assert!(person_name("   ").is_err());
+        assert!(person_name(&"x".repeat(41)).is_err());
+        assert!(person_name("Tho\nmas").is_err());
+    }
+
+    #[test]
+    fn nobt_titles_are_trimmed_and_checked() {
+        assert_eq!(nobt_title(" Road Trip "), Ok("Road Trip"));
+        assert!(nobt_title("").is_err());
+        assert!(nobt_title(&"x".repeat(61)).is_err());
+        assert!(nobt_title("Road\tTrip").is_err());
+    }
+
+    #[test]
+    fn category_names_are_trimmed_and_checked() {
+        assert_eq!(category_name(" Skiing "), Ok("Skiing"));
+        assert!(category_name(&"x".repeat(31)).is_err());
+        assert!(category_name("Ski\u{0}ing").is_err());
+    }
+}